@@ -18,17 +18,26 @@ use std::fmt;
 use std::mem;
 use std::ptr;
 use std::slice;
+use std::thread;
 use std::cmp::{self, Ordering};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::collections::hash_map::{DefaultHasher};
+use std::hash::{Hash, Hasher};
 use std::marker::{PhantomData};
-use std::path::{PathBuf};
+use std::path::{Path, PathBuf};
 
 use clang_sys::*;
 
 use libc::{c_uint};
+#[cfg(feature="serde")]
+use serde::{Deserialize, Serialize};
 
 use utility;
 use super::{Availability, EntityKind, TranslationUnit, Unsaved, Usr};
 use super::diagnostic::{Diagnostic};
+use super::documentation::{Comment};
+#[cfg(feature="clang_7_0")]
+use super::source::{SourceRange};
 
 //================================================
 // Enums
@@ -118,6 +127,123 @@ impl<'r> CompletionChunk<'r> {
     pub fn is_optional(&self) -> bool {
         matches!(*self, CompletionChunk::Optional(_))
     }
+
+    //- Consumers --------------------------------
+
+    /// Returns an owned snapshot of this completion chunk.
+    pub fn snapshot(&self) -> CompletionChunkSnapshot {
+        match *self {
+            CompletionChunk::Colon => CompletionChunkSnapshot::Colon,
+            CompletionChunk::Comma => CompletionChunkSnapshot::Comma,
+            CompletionChunk::Equals => CompletionChunkSnapshot::Equals,
+            CompletionChunk::Semicolon => CompletionChunkSnapshot::Semicolon,
+            CompletionChunk::LeftAngleBracket => CompletionChunkSnapshot::LeftAngleBracket,
+            CompletionChunk::RightAngleBracket => CompletionChunkSnapshot::RightAngleBracket,
+            CompletionChunk::LeftBrace => CompletionChunkSnapshot::LeftBrace,
+            CompletionChunk::RightBrace => CompletionChunkSnapshot::RightBrace,
+            CompletionChunk::LeftParenthesis => CompletionChunkSnapshot::LeftParenthesis,
+            CompletionChunk::RightParenthesis => CompletionChunkSnapshot::RightParenthesis,
+            CompletionChunk::LeftSquareBracket => CompletionChunkSnapshot::LeftSquareBracket,
+            CompletionChunk::RightSquareBracket => CompletionChunkSnapshot::RightSquareBracket,
+            CompletionChunk::HorizontalSpace(ref text) =>
+                CompletionChunkSnapshot::HorizontalSpace(text.clone()),
+            CompletionChunk::VerticalSpace(ref text) =>
+                CompletionChunkSnapshot::VerticalSpace(text.clone()),
+            CompletionChunk::CurrentParameter(ref text) =>
+                CompletionChunkSnapshot::CurrentParameter(text.clone()),
+            CompletionChunk::Informative(ref text) =>
+                CompletionChunkSnapshot::Informative(text.clone()),
+            CompletionChunk::Placeholder(ref text) =>
+                CompletionChunkSnapshot::Placeholder(text.clone()),
+            CompletionChunk::ResultType(ref text) =>
+                CompletionChunkSnapshot::ResultType(text.clone()),
+            CompletionChunk::Text(ref text) => CompletionChunkSnapshot::Text(text.clone()),
+            CompletionChunk::TypedText(ref text) =>
+                CompletionChunkSnapshot::TypedText(text.clone()),
+            CompletionChunk::Optional(ref string) => CompletionChunkSnapshot::Optional(
+                string.get_chunks().iter().map(|c| c.snapshot()).collect()
+            ),
+        }
+    }
+}
+
+// CompletionChunkSnapshot _______________________
+
+/// An owned snapshot of a piece of a code completion string.
+///
+/// Unlike `CompletionChunk`, a snapshot does not borrow from the `CompletionResults` it was taken
+/// from and can therefore outlive it (e.g., to be serialized and cached between runs).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub enum CompletionChunkSnapshot {
+    /// A colon (`':'`).
+    Colon,
+    /// A comma (`','`).
+    Comma,
+    /// An equals sign (`'='`).
+    Equals,
+    /// A semicolon (`';'`).
+    Semicolon,
+    /// A left angle bracket (`'<'`).
+    LeftAngleBracket,
+    /// A right angle bracket (`'>'`).
+    RightAngleBracket,
+    /// A left brace (`'{'`).
+    LeftBrace,
+    /// A right brace (`'}'`).
+    RightBrace,
+    /// A left parentesis (`'('`)).
+    LeftParenthesis,
+    /// A right parenthesis (`')'`).
+    RightParenthesis,
+    /// A left square bracket (`'['`).
+    LeftSquareBracket,
+    /// A right square bracket (`']'`).
+    RightSquareBracket,
+    /// Horizontal space (e.g., `' '`).
+    HorizontalSpace(String),
+    /// Vertical space (e.g., `'\n'`).
+    VerticalSpace(String),
+    /// Text that describes the current parameter when code completion was run on a function call,
+    /// message send, or template specialization.
+    CurrentParameter(String),
+    /// Informative text that should be displayed but not inserted as part of the template.
+    Informative(String),
+    /// Text that should be replaced by the user.
+    Placeholder(String),
+    /// Text that specifies the result type of the containing result.
+    ResultType(String),
+    /// Text that should be inserted.
+    Text(String),
+    /// Text that the user would be expected to type to get the containing code completion result.
+    TypedText(String),
+    /// The chunks of an optional piece that could be part of the template but is not required.
+    Optional(Vec<CompletionChunkSnapshot>),
+}
+
+// MemberCompletions _____________________________
+
+/// The code completion results for the members of a struct, class, or union.
+///
+/// See `Entity::complete_members` for how each variant is produced.
+#[derive(Debug)]
+pub enum MemberCompletions<'tu> {
+    /// Results produced by running code completion at a synthetic member access.
+    Completed(CompletionResults),
+    /// Results synthesized from the completion strings of the member declarations.
+    Declared(Vec<CompletionResult<'tu>>),
+}
+
+impl<'tu> MemberCompletions<'tu> {
+    //- Accessors --------------------------------
+
+    /// Returns the code completion results for the members.
+    pub fn get_results(&self) -> Vec<CompletionResult<'_>> {
+        match *self {
+            MemberCompletions::Completed(ref results) => results.get_results(),
+            MemberCompletions::Declared(ref results) => results.clone(),
+        }
+    }
 }
 
 //================================================
@@ -142,6 +268,9 @@ builder! {
         pub code_patterns: CXCodeComplete_IncludeCodePatterns,
         /// Sets whether documentation comment briefs will be included in code completion results.
         pub briefs: CXCodeComplete_IncludeBriefComments,
+        /// Sets whether code completion results that require fix-its will be included (ignored
+        /// before `libclang` 7.0).
+        pub fixits: CXCodeComplete_IncludeCompletionsWithFixIts,
     }
 }
 
@@ -151,15 +280,26 @@ impl<'tu> Completer<'tu> {
     #[doc(hidden)]
     pub fn new<F: Into<PathBuf>>(
         tu: &'tu TranslationUnit<'tu>, file: F, line: u32, column: u32
+    ) -> Completer<'tu> {
+        Completer::with_flags(tu, file, line, column, default_completion_flags())
+    }
+
+    /// Constructs a new `Completer` which runs code completion with the supplied options.
+    ///
+    /// See `default_completion_flags` for the options used by `TranslationUnit::completer`.
+    pub fn with_flags<F: Into<PathBuf>>(
+        tu: &'tu TranslationUnit<'tu>, file: F, line: u32, column: u32, flags: CompletionFlags
     ) -> Completer<'tu> {
         let file = file.into();
-        let flags = unsafe { clang_defaultCodeCompleteOptions() };
-        Completer { tu, file, line, column, unsaved: vec![], flags }
+        Completer { tu, file, line, column, unsaved: vec![], flags: flags.into() }
     }
 
     //- Mutators ---------------------------------
 
     /// Sets the unsaved files to use.
+    ///
+    /// Unsaved files that are ignored are reported to the hook set by
+    /// `Clang::set_unused_unsaved_hook` (see `TranslationUnit::get_unused_unsaved`).
     pub fn unsaved(&mut self, unsaved: &[Unsaved]) -> &mut Completer<'tu> {
         self.unsaved = unsaved.into();
         self
@@ -167,20 +307,206 @@ impl<'tu> Completer<'tu> {
 
     //- Accessors --------------------------------
 
+    /// Returns whether macros will be included in code completion results.
+    pub fn macros_enabled(&self) -> bool {
+        self.flags & CXCodeComplete_IncludeMacros != 0
+    }
+
+    /// Returns whether code patterns (e.g., for loops) will be included in code completion results.
+    pub fn code_patterns_enabled(&self) -> bool {
+        self.flags & CXCodeComplete_IncludeCodePatterns != 0
+    }
+
+    /// Returns whether documentation comment briefs will be included in code completion results.
+    pub fn briefs_enabled(&self) -> bool {
+        self.flags & CXCodeComplete_IncludeBriefComments != 0
+    }
+
     /// Runs code completion.
     pub fn complete(&self) -> CompletionResults {
-        unsafe {
-            let ptr = clang_codeCompleteAt(
-                self.tu.ptr,
-                utility::from_path(&self.file).as_ptr(),
-                self.line as c_uint,
-                self.column as c_uint,
-                self.unsaved.as_ptr() as *mut CXUnsavedFile,
-                self.unsaved.len() as c_uint,
-                self.flags,
-            );
-            CompletionResults::from_ptr(ptr)
+        let (file, unsaved) = (&self.file, &self.unsaved);
+        let ptr = complete(self.tu.ptr, file, self.line, self.column, unsaved, self.flags);
+        self.tu.report_unused_unsaved(unsaved);
+        CompletionResults::from_ptr(ptr)
+    }
+
+    /// Runs code completion and returns snapshots of the best code completion results for the
+    /// supplied query.
+    ///
+    /// Code completion results are ranked by their fuzzy match scores for the query (see
+    /// `fuzzy_score`) with ties broken by priority, the length of the typed text, and then the
+    /// typed text itself. Code completion results that do not match the query or that have no
+    /// typed text are discarded, as are code completion results with the same typed text as a
+    /// better ranked code completion result. At most `limit` snapshots are returned.
+    pub fn complete_ranked(&self, query: &str, limit: usize) -> Vec<CompletionResultSnapshot> {
+        let results = self.complete();
+        let mut ranked = results.iter_results().filter_map(|r| {
+            let text = r.string.get_typed_text()?;
+            let score = fuzzy_score(query, &text)?;
+            Some((cmp::Reverse(score), r.string.get_priority(), text.len(), text, r))
+        }).collect::<Vec<_>>();
+        ranked.sort_by(|a, b| (a.0, a.1, a.2, &a.3).cmp(&(b.0, b.1, b.2, &b.3)));
+
+        let mut seen = HashSet::new();
+        ranked.into_iter().filter(|r| seen.insert(r.3.clone())).take(limit).map(|r| {
+            r.4.snapshot()
+        }).collect()
+    }
+
+    /// Runs code completion and returns snapshots of the code completion results.
+    pub fn complete_snapshots(&self) -> Vec<CompletionResultSnapshot> {
+        let mut snapshots = vec![];
+        self.complete_snapshots_into(&mut snapshots);
+        snapshots
+    }
+
+    /// Runs code completion and replaces the contents of the supplied buffer with snapshots of the
+    /// code completion results.
+    ///
+    /// Reusing the buffer (e.g., on every keystroke in an editor) reuses the allocation of the
+    /// outer `Vec` but the text of each chunk of each snapshot is still allocated. `libclang` also
+    /// allocates a new set of code completion results every time code completion is run.
+    ///
+    /// There is no method that refills a `Vec<CompletionResult>` instead. A `CompletionResult`
+    /// borrows from the `CompletionResults` it was taken from, and every run of code completion
+    /// produces a new `CompletionResults`. A `Vec` of borrowed results therefore cannot outlive the
+    /// run that filled it and cannot be reused for the next run. Use this method to reuse a buffer
+    /// of results across runs and `CompletionString::get_chunks_into` to reuse a buffer of chunks
+    /// across the results of a single run.
+    pub fn complete_snapshots_into(&self, snapshots: &mut Vec<CompletionResultSnapshot>) {
+        snapshots.clear();
+        let results = self.complete();
+        snapshots.extend(results.iter_results().map(|r| r.snapshot()));
+    }
+
+    /// Runs code completion on a new thread and returns snapshots of the code completion results.
+    ///
+    /// The code completion results are created, snapshotted, and disposed of entirely on the new
+    /// thread so only the snapshots (which are `Send`) are returned to this thread. The new thread
+    /// is given a large stack (8 MiB) as recommended for `libclang`, which makes this useful when
+    /// the current thread has a small stack.
+    ///
+    /// # Threading
+    ///
+    /// A translation unit must never be used by multiple threads at the same time. This method
+    /// upholds that contract by blocking the current thread until the new thread has finished, so
+    /// the translation unit is only ever used by one thread at a time.
+    pub fn complete_snapshots_on_thread(&self) -> Vec<CompletionResultSnapshot> {
+        struct SendTranslationUnit(CXTranslationUnit);
+        unsafe impl Send for SendTranslationUnit { }
+
+        #[cfg(feature="runtime")]
+        let library = clang_sys::get_library();
+        let tu = SendTranslationUnit(self.tu.ptr);
+        let (file, line, column, unsaved, flags) =
+            (&self.file, self.line, self.column, &self.unsaved, self.flags);
+        let snapshots = thread::scope(|scope| {
+            let worker = thread::Builder::new().stack_size(8 << 20).spawn_scoped(scope, move || {
+                #[cfg(feature="runtime")]
+                clang_sys::set_library(library);
+                let ptr = complete(tu.0, file, line, column, unsaved, flags);
+                let results = CompletionResults::from_ptr(ptr);
+                results.iter_results().map(|r| r.snapshot()).collect()
+            });
+            worker.expect("failed to spawn code completion thread").join().unwrap()
+        });
+        self.tu.report_unused_unsaved(&self.unsaved);
+        snapshots
+    }
+
+    fn get_cache_key(&self) -> CompletionCacheKey {
+        let mut hasher = DefaultHasher::new();
+        for unsaved in &self.unsaved {
+            unsaved.path.as_bytes().hash(&mut hasher);
+            unsaved.contents.as_bytes().hash(&mut hasher);
         }
+        (self.file.clone(), self.line, self.column, hasher.finish(), self.flags)
+    }
+}
+
+// CompletionCache _______________________________
+
+type CompletionCacheKey = (PathBuf, u32, u32, u64, CXCodeComplete_Flags);
+
+/// A cache of code completion results which evicts the least recently used entries when full.
+///
+/// Entries are keyed by the file, line, column, and options of a code completion run along with a
+/// hash of the paths and contents of the unsaved files it uses. Changes to the translation unit
+/// itself (e.g., reparsing it) are not detected, so the cache should be cleared when that happens.
+#[derive(Clone, Debug)]
+pub struct CompletionCache {
+    capacity: usize,
+    entries: VecDeque<(CompletionCacheKey, Vec<CompletionResultSnapshot>)>,
+}
+
+impl CompletionCache {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `CompletionCache` that holds at most the supplied number of entries.
+    ///
+    /// # Panics
+    ///
+    /// * `capacity` is `0`
+    pub fn new(capacity: usize) -> CompletionCache {
+        assert!(capacity != 0, "capacity must be greater than 0");
+        CompletionCache { capacity, entries: VecDeque::with_capacity(capacity) }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the maximum number of entries in this cache.
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of entries in this cache.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether this cache has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns whether this cache has an entry for the supplied code completion run.
+    pub fn contains(&self, completer: &Completer) -> bool {
+        let key = completer.get_cache_key();
+        self.entries.iter().any(|e| e.0 == key)
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Removes all of the entries in this cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns snapshots of the code completion results for the supplied code completion run,
+    /// running code completion only if they are not already in this cache.
+    ///
+    /// This is an alias for `get_or_complete`.
+    pub fn complete(&mut self, completer: &Completer) -> &[CompletionResultSnapshot] {
+        self.get_or_complete(completer)
+    }
+
+    /// Returns snapshots of the code completion results for the supplied code completion run,
+    /// running code completion only if they are not already in this cache.
+    ///
+    /// If code completion is run and this cache is full, the least recently used entry is evicted.
+    pub fn get_or_complete(&mut self, completer: &Completer) -> &[CompletionResultSnapshot] {
+        let key = completer.get_cache_key();
+        if let Some(index) = self.entries.iter().position(|e| e.0 == key) {
+            let entry = self.entries.remove(index).unwrap();
+            self.entries.push_front(entry);
+        } else {
+            let snapshots = completer.complete_snapshots();
+            if self.entries.len() == self.capacity {
+                self.entries.pop_back();
+            }
+            self.entries.push_front((key, snapshots));
+        }
+        &self.entries[0].1
     }
 }
 
@@ -239,6 +565,105 @@ options! {
     }
 }
 
+impl CompletionContext {
+    //- Accessors --------------------------------
+
+    /// Returns the characters that trigger code completion in this context.
+    ///
+    /// Multi-character triggers are represented by their last character (i.e., `>` for `->` and
+    /// `:` for `::`).
+    pub fn trigger_characters(&self) -> Vec<char> {
+        let mut characters = vec![];
+        if self.dot_members || self.objc_property_members {
+            characters.push('.');
+        }
+        if self.arrow_members {
+            characters.push('>');
+        }
+        if self.nested_name_specifiers {
+            characters.push(':');
+        }
+        if self.objc_protocols || self.objc_selector_names {
+            characters.push('@');
+        }
+        if self.objc_instance_messages || self.objc_class_messages {
+            characters.push('[');
+        }
+        characters
+    }
+}
+
+// CompletionFlags _______________________________
+
+options! {
+    /// A set of options that determines how code completion is run.
+    options CompletionFlags: CXCodeComplete_Flags {
+        /// Indicates whether macros will be included in code completion results.
+        pub macros: CXCodeComplete_IncludeMacros,
+        /// Indicates whether code patterns (e.g., for loops) will be included in code completion
+        /// results.
+        pub code_patterns: CXCodeComplete_IncludeCodePatterns,
+        /// Indicates whether documentation comment briefs will be included in code completion
+        /// results.
+        pub briefs: CXCodeComplete_IncludeBriefComments,
+        /// Indicates whether the preamble will be skipped when running code completion (ignored
+        /// before `libclang` 6.0).
+        pub skip_preamble: CXCodeComplete_SkipPreamble,
+        /// Indicates whether code completion results that require fix-its will be included (ignored
+        /// before `libclang` 7.0).
+        pub fixits: CXCodeComplete_IncludeCompletionsWithFixIts,
+    }
+}
+
+// CompletionIndex _______________________________
+
+/// An index of code completion results that supports fast typed text prefix queries.
+///
+/// The index is built once from a set of code completion results and can then be queried for each
+/// keystroke. Each query is a pair of binary searches over the results sorted by typed text, so
+/// narrowing a query as more characters are typed never rescans all of the results.
+#[derive(Clone, Debug)]
+pub struct CompletionIndex<'r> {
+    keys: Vec<String>,
+    results: Vec<CompletionResult<'r>>,
+}
+
+impl<'r> CompletionIndex<'r> {
+    //- Constructors -----------------------------
+
+    /// Constructs a new `CompletionIndex` from the supplied code completion results.
+    pub fn new(results: &'r CompletionResults) -> CompletionIndex<'r> {
+        let mut entries = results.iter_results().map(|r| {
+            (r.string.get_typed_text().unwrap_or_default(), r)
+        }).collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let (keys, results) = entries.into_iter().unzip();
+        CompletionIndex { keys, results }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the number of code completion results in this index.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Returns whether this index has no code completion results.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Returns the code completion results in this index whose typed text starts with the supplied
+    /// prefix (case-sensitive), sorted by typed text.
+    ///
+    /// Code completion results without typed text are only returned for an empty prefix.
+    pub fn query(&self, prefix: &str) -> &[CompletionResult<'r>] {
+        let start = self.keys.partition_point(|k| k.as_str() < prefix);
+        let count = self.keys[start..].partition_point(|k| k.starts_with(prefix));
+        &self.results[start..start + count]
+    }
+}
+
 // CompletionResult ______________________________
 
 /// A code completion result.
@@ -257,6 +682,71 @@ impl<'r> CompletionResult<'r> {
         let kind = unsafe { mem::transmute(raw.CursorKind) };
         CompletionResult { kind, string: CompletionString::from_ptr(raw.CompletionString) }
     }
+
+    //- Accessors --------------------------------
+
+    /// Returns the parsed documentation comment associated with the declaration this code
+    /// completion result refers to, if applicable.
+    ///
+    /// The declaration is resolved by searching the supplied translation unit for a declaration
+    /// with the same kind, name, and semantic parent name as this code completion result, which
+    /// makes this considerably more expensive than `CompletionString::get_comment_brief`.
+    pub fn resolve_documentation<'tu>(&self, tu: &'tu TranslationUnit<'tu>) -> Option<Comment<'tu>> {
+        let name = self.string.get_typed_text()?;
+        let parent = self.string.get_parent_name();
+        tu.get_entities(|e| {
+            e.get_kind() == self.kind &&
+            e.get_name().as_ref() == Some(&name) &&
+            e.get_semantic_parent().and_then(|p| p.get_name()) == parent
+        }).into_iter().filter_map(|e| e.get_parsed_comment()).next()
+    }
+
+    /// Returns the fix-its that must be applied before this code completion result is inserted.
+    ///
+    /// This code completion result must have been retrieved from the supplied set of code
+    /// completion results, otherwise no fix-its are returned. See `CompletionResults::get_fix_its`.
+    #[cfg(feature="clang_7_0")]
+    pub fn get_fix_its<'tu>(
+        &self, results: &CompletionResults, tu: &'tu TranslationUnit<'tu>
+    ) -> Vec<(String, SourceRange<'tu>)> {
+        let raws = results.get_raw_results();
+        match raws.iter().position(|r| r.CompletionString == self.string.ptr) {
+            Some(index) => results.get_fix_its(index, tu),
+            None => vec![],
+        }
+    }
+
+    /// Returns the text of the typed text chunk for this code completion result, if any.
+    ///
+    /// See `CompletionString::get_typed_text`.
+    pub fn typed_text(&self) -> Option<String> {
+        self.string.get_typed_text()
+    }
+
+    /// Returns whether inserting this code completion result would produce a call (i.e., whether
+    /// its completion string has a parenthesized parameter list after its typed text chunk).
+    ///
+    /// This is the case for functions, methods, constructors, and function-like macros.
+    pub fn is_callable(&self) -> bool {
+        self.string.get_chunks().iter().skip_while(|c| {
+            !matches!(c, CompletionChunk::TypedText(_))
+        }).any(|c| *c == CompletionChunk::LeftParenthesis)
+    }
+
+    //- Consumers --------------------------------
+
+    /// Returns an owned snapshot of this code completion result.
+    pub fn snapshot(&self) -> CompletionResultSnapshot {
+        CompletionResultSnapshot {
+            kind: self.kind,
+            priority: self.string.get_priority(),
+            availability: self.string.get_availability(),
+            annotations: self.string.get_annotations(),
+            comment_brief: self.string.get_comment_brief(),
+            parent_name: self.string.get_parent_name(),
+            chunks: self.string.get_chunks().iter().map(|c| c.snapshot()).collect(),
+        }
+    }
 }
 
 impl<'r> cmp::PartialOrd for CompletionResult<'r> {
@@ -271,6 +761,32 @@ impl<'r> cmp::Ord for CompletionResult<'r> {
     }
 }
 
+// CompletionResultSnapshot ______________________
+
+/// An owned snapshot of a code completion result.
+///
+/// Unlike `CompletionResult`, a snapshot does not borrow from the `CompletionResults` it was taken
+/// from and can therefore outlive it (e.g., to be serialized and cached between runs).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub struct CompletionResultSnapshot {
+    /// The categorization of the AST entity this code completion result produces.
+    pub kind: EntityKind,
+    /// The priority of this code completion result (smaller values indicate higher priorities).
+    pub priority: usize,
+    /// The availability of this code completion result.
+    pub availability: Availability,
+    /// The annotations associated with this code completion result.
+    pub annotations: Vec<String>,
+    /// The documentation comment brief associated with this code completion result, if any.
+    pub comment_brief: Option<String>,
+    /// The name of the semantic parent of the declaration this code completion result refers to,
+    /// if applicable.
+    pub parent_name: Option<String>,
+    /// The chunks of this code completion result.
+    pub chunks: Vec<CompletionChunkSnapshot>,
+}
+
 // CompletionResults _____________________________
 
 /// A set of code completion results.
@@ -319,6 +835,56 @@ impl CompletionResults {
         }
     }
 
+    /// Returns the code completion results in this set of code completion results whose typed
+    /// text starts with the supplied prefix (case-sensitive).
+    ///
+    /// Only the typed text of each code completion result is retrieved (see
+    /// `CompletionString::get_typed_text`) and only the matching code completion results are
+    /// collected. Code completion results without typed text are always excluded.
+    pub fn filtered(&self, prefix: &str) -> Vec<CompletionResult<'_>> {
+        self.iter_results().filter(|r| {
+            r.string.get_typed_text().is_some_and(|t| t.starts_with(prefix))
+        }).collect()
+    }
+
+    /// Returns the code completion results in this set of code completion results whose typed
+    /// text starts with the supplied prefix (ASCII case-insensitive).
+    ///
+    /// See `filtered`.
+    pub fn filtered_ci(&self, prefix: &str) -> Vec<CompletionResult<'_>> {
+        self.iter_results().filter(|r| {
+            r.string.get_typed_text().is_some_and(|t| {
+                t.len() >= prefix.len() &&
+                t.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+            })
+        }).collect()
+    }
+
+    /// Returns the fix-its that must be applied before the code completion result at the supplied
+    /// index in this set of code completion results is inserted, if any.
+    ///
+    /// Each fix-it consists of the replacement text and the source range to replace (e.g., `->`
+    /// replacing `.` when completing a member of a pointer to a record). The source ranges refer to
+    /// the contents of the unsaved files used for code completion where applicable, not the
+    /// contents on disk. No fix-its are returned if the index is out of bounds.
+    #[cfg(feature="clang_7_0")]
+    pub fn get_fix_its<'tu>(
+        &self, index: usize, tu: &'tu TranslationUnit<'tu>
+    ) -> Vec<(String, SourceRange<'tu>)> {
+        if index >= self.get_raw_results().len() {
+            return vec![];
+        }
+        unsafe {
+            let count = clang_getCompletionNumFixIts(self.ptr, index as c_uint);
+            (0..count).map(|i| {
+                let mut range = mem::MaybeUninit::uninit();
+                let index = index as c_uint;
+                let text = clang_getCompletionFixIt(self.ptr, index, i, range.as_mut_ptr());
+                (utility::to_string(text), SourceRange::from_raw(range.assume_init(), tu))
+            }).collect()
+        }
+    }
+
     /// Returns the selector or partial selector that has been entered this far for the Objective-C
     /// message send context for this set of code completion results.
     pub fn get_objc_selector(&self) -> Option<String> {
@@ -333,9 +899,61 @@ impl CompletionResults {
 
     /// Returns the code completion results in this set of code completion results.
     pub fn get_results(&self) -> Vec<CompletionResult> {
+        self.iter_results().collect()
+    }
+
+    /// Returns the code completion results in this set of code completion results grouped by the
+    /// categorization of the AST entities they produce.
+    ///
+    /// The code completion results in each group are in the same order as in `get_results`.
+    pub fn group_by_kind(&self) -> BTreeMap<EntityKind, Vec<CompletionResult<'_>>> {
+        let mut groups = BTreeMap::new();
+        for result in self.iter_results() {
+            groups.entry(result.kind).or_insert_with(Vec::new).push(result);
+        }
+        groups
+    }
+
+    /// Returns an iterator over the code completion results in this set of code completion results.
+    ///
+    /// Unlike `get_results`, the code completion results are constructed as they are requested
+    /// so no intermediate `Vec` is allocated.
+    pub fn iter_results(&self) -> impl ExactSizeIterator<Item=CompletionResult<'_>> + '_ {
+        self.get_raw_results().iter().cloned().map(CompletionResult::from_raw)
+    }
+
+    fn get_raw_results(&self) -> &[CXCompletionResult] {
+        unsafe {
+            let (results, count) = ((*self.ptr).Results, (*self.ptr).NumResults as usize);
+            if results.is_null() || count == 0 {
+                &[]
+            } else {
+                slice::from_raw_parts(results, count)
+            }
+        }
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Sorts the code completion results in this set of code completion results in place.
+    ///
+    /// The results are sorted by priority (ascending) and then by typed text. The typed text is
+    /// compared as `libclang` compares it (`clang_sortCodeCompletionResults`): case-insensitively
+    /// first with ties broken case-sensitively. This matches the `Ord` implementation for
+    /// `CompletionString` unless the typed text of two results differ only in case.
+    ///
+    /// This is much faster than sorting the results returned by `get_results` since the underlying
+    /// array is sorted directly and each priority is only retrieved once.
+    pub fn sort(&mut self) {
         unsafe {
-            let raws = slice::from_raw_parts((*self.ptr).Results, (*self.ptr).NumResults as usize);
-            raws.iter().cloned().map(CompletionResult::from_raw).collect()
+            let results = (*self.ptr).Results;
+            let count = (*self.ptr).NumResults;
+            if results.is_null() || count == 0 {
+                return;
+            }
+            clang_sortCodeCompletionResults(results, count);
+            let raws = slice::from_raw_parts_mut(results, count as usize);
+            raws.sort_by_cached_key(|r| clang_getCompletionPriority(r.CompletionString));
         }
     }
 }
@@ -406,18 +1024,115 @@ impl<'r> CompletionString<'r> {
     }
 
     /// Returns the text of the typed text chunk for this completion string, if any.
+    ///
+    /// Only the text of the typed text chunk is retrieved, so this is much cheaper than searching
+    /// the chunks returned by `get_chunks`.
     pub fn get_typed_text(&self) -> Option<String> {
-        for chunk in self.get_chunks() {
-            if let CompletionChunk::TypedText(text) = chunk {
-                return Some(text);
+        let mut kinds = iter!(
+            clang_getNumCompletionChunks(self.ptr),
+            clang_getCompletionChunkKind(self.ptr),
+        );
+        let index = kinds.position(|k| k == CXCompletionChunk_TypedText)?;
+        let text = unsafe { clang_getCompletionChunkText(self.ptr, index as c_uint) };
+        Some(unsafe { utility::to_string(text) })
+    }
+
+    /// Returns the label for this completion string (i.e., the text of the typed text chunk), or an
+    /// empty string if there is no typed text chunk.
+    ///
+    /// This is intended to be displayed in a list of code completion results alongside the text
+    /// returned by `get_detail`.
+    pub fn get_label(&self) -> String {
+        self.get_typed_text().unwrap_or_default()
+    }
+
+    /// Returns the full signature of this completion string including the result type, if any
+    /// (e.g., `int add(int a, int b)`).
+    ///
+    /// All chunks (including optional chunks) are included except for the result type chunk, which
+    /// is instead prepended to the signature and separated from it by a space.
+    pub fn get_detail(&self) -> String {
+        let chunks = self.get_chunks();
+        let mut detail = String::new();
+        for chunk in &chunks {
+            if let CompletionChunk::ResultType(ref text) = *chunk {
+                detail.push_str(text);
+                detail.push(' ');
             }
         }
-        None
+        write_signature(&chunks, &mut detail);
+        detail
+    }
+
+    /// Returns the text to insert for this completion string (e.g., `add()`).
+    ///
+    /// Only the typed text, text, space, and punctuation chunks that are not part of an optional
+    /// chunk are included. Placeholder chunks and the comma chunks that separate them are omitted,
+    /// as are current parameter, informative, and result type chunks.
+    pub fn get_insert_text(&self) -> String {
+        self.get_chunks().iter().filter_map(|c| match *c {
+            CompletionChunk::Comma |
+            CompletionChunk::CurrentParameter(_) |
+            CompletionChunk::Informative(_) |
+            CompletionChunk::Placeholder(_) |
+            CompletionChunk::ResultType(_) |
+            CompletionChunk::Optional(_) => None,
+            _ => c.get_text(),
+        }).collect()
+    }
+
+    /// Returns the text to insert for this completion string and the offset in that text where the
+    /// cursor should be placed after insertion, if not at the end of the text.
+    ///
+    /// The text consists of the typed text followed by `()` if this completion string has an
+    /// argument list. The cursor is placed between the parentheses if the argument list has any
+    /// placeholders.
+    pub fn to_plain_insert(&self) -> (String, Option<usize>) {
+        let mut text = self.get_typed_text().unwrap_or_default();
+
+        let chunks = self.get_chunks();
+        let mut chunks = chunks.iter().skip_while(|c| **c != CompletionChunk::LeftParenthesis);
+        if chunks.next().is_none() {
+            return (text, None);
+        }
+
+        let placeholders = chunks.take_while(|c| **c != CompletionChunk::RightParenthesis).any(|c| {
+            matches!(*c, CompletionChunk::Placeholder(_) | CompletionChunk::Optional(_))
+        });
+
+        text.push('(');
+        let cursor = if placeholders { Some(text.len()) } else { None };
+        text.push(')');
+        (text, cursor)
+    }
+
+    /// Returns this completion string rendered as a Language Server Protocol snippet.
+    ///
+    /// Placeholder and current parameter chunks become numbered tab stops (e.g., `${1:int x}`),
+    /// informative and result type chunks are dropped, and all other chunks are inserted verbatim
+    /// (commas are followed by a space). Each optional chunk becomes a single tab stop that
+    /// contains its own (nested) tab stops. Tab stops are numbered from left to right starting at
+    /// `1`. The characters `$`, `}`, and `\` are escaped wherever they appear in the text.
+    pub fn to_snippet(&self) -> String {
+        let mut snippet = String::new();
+        write_snippet(&self.get_chunks(), &mut 1, &mut snippet);
+        snippet
     }
 
     /// Returns the chunks of this completion string.
     pub fn get_chunks(&self) -> Vec<CompletionChunk> {
-        iter!(
+        let mut chunks = vec![];
+        self.get_chunks_into(&mut chunks);
+        chunks
+    }
+
+    /// Replaces the contents of the supplied buffer with the chunks of this completion string.
+    ///
+    /// Reusing the buffer (e.g., for every code completion result in a set of code completion
+    /// results) reuses the allocation of the `Vec` but the text of each chunk is still allocated.
+    pub fn get_chunks_into(&self, chunks: &mut Vec<CompletionChunk<'r>>) {
+        chunks.clear();
+        chunks.extend(iter!(
             clang_getNumCompletionChunks(self.ptr),
             clang_getCompletionChunkKind(self.ptr),
         ).enumerate().map(|(i, k)| {
@@ -456,7 +1171,7 @@ impl<'r> CompletionString<'r> {
                 },
                 _ => panic!("unexpected completion chunk kind: {:?}", k),
             }
-        }).collect()
+        }));
     }
 }
 
@@ -490,3 +1205,110 @@ impl<'r> cmp::Ord for CompletionString<'r> {
         }
     }
 }
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the default options for running code completion.
+pub fn default_completion_flags() -> CompletionFlags {
+    unsafe { CompletionFlags::from(clang_defaultCodeCompleteOptions()) }
+}
+
+/// Returns a score that indicates how well the supplied candidate (e.g., the typed text of a code
+/// completion result) matches the supplied fuzzy query, if it matches at all.
+///
+/// A candidate matches if the characters in the query appear in the candidate in the same order,
+/// compared ASCII case-insensitively. Higher scores indicate better matches: matching characters
+/// with the same case, at the start of the candidate, immediately after the previous match, or at
+/// the start of a word (e.g., after `_` or at a lowercase to uppercase transition) all increase
+/// the score. An empty query matches every candidate with a score of `0`.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate = candidate.chars().collect::<Vec<_>>();
+    let (mut score, mut start, mut previous) = (0, 0, None);
+    for q in query.chars() {
+        let offset = candidate[start..].iter().position(|c| c.eq_ignore_ascii_case(&q))?;
+        let index = start + offset;
+        score += if candidate[index] == q { 2 } else { 1 };
+        if index == 0 {
+            score += 8;
+        } else if previous == Some(index - 1) {
+            score += 5;
+        } else {
+            let (before, current) = (candidate[index - 1], candidate[index]);
+            if !before.is_alphanumeric() || (before.is_lowercase() && current.is_uppercase()) {
+                score += 3;
+            }
+        }
+        previous = Some(index);
+        start = index + 1;
+    }
+    Some(score)
+}
+
+fn complete(
+    tu: CXTranslationUnit,
+    file: &Path,
+    line: u32,
+    column: u32,
+    unsaved: &[Unsaved],
+    flags: CXCodeComplete_Flags,
+) -> *mut CXCodeCompleteResults {
+    let unsaved = unsaved.iter().map(|u| u.as_raw()).collect::<Vec<_>>();
+    unsafe {
+        clang_codeCompleteAt(
+            tu,
+            utility::from_path(file).as_ptr(),
+            line as c_uint,
+            column as c_uint,
+            unsaved.as_ptr() as *mut CXUnsavedFile,
+            unsaved.len() as c_uint,
+            flags,
+        )
+    }
+}
+
+fn write_signature(chunks: &[CompletionChunk], signature: &mut String) {
+    for chunk in chunks {
+        match *chunk {
+            CompletionChunk::Comma => signature.push_str(", "),
+            CompletionChunk::ResultType(_) => { },
+            CompletionChunk::Optional(ref string) => {
+                write_signature(&string.get_chunks(), signature);
+            },
+            _ => signature.push_str(&chunk.get_text().unwrap_or_default()),
+        }
+    }
+}
+
+fn write_snippet(chunks: &[CompletionChunk], next: &mut usize, snippet: &mut String) {
+    fn push_escaped(text: &str, snippet: &mut String) {
+        for c in text.chars() {
+            if c == '$' || c == '}' || c == '\\' {
+                snippet.push('\\');
+            }
+            snippet.push(c);
+        }
+    }
+
+    for chunk in chunks {
+        match *chunk {
+            CompletionChunk::Comma => snippet.push_str(", "),
+            CompletionChunk::CurrentParameter(ref text) |
+            CompletionChunk::Placeholder(ref text) => {
+                snippet.push_str(&format!("${{{}:", next));
+                *next += 1;
+                push_escaped(text, snippet);
+                snippet.push('}');
+            },
+            CompletionChunk::Informative(_) | CompletionChunk::ResultType(_) => { },
+            CompletionChunk::Optional(ref string) => {
+                snippet.push_str(&format!("${{{}:", next));
+                *next += 1;
+                write_snippet(&string.get_chunks(), next, snippet);
+                snippet.push('}');
+            },
+            _ => push_escaped(&chunk.get_text().unwrap_or_default(), snippet),
+        }
+    }
+}
@@ -17,11 +17,16 @@
 use std::fmt;
 use std::mem;
 use std::cmp::{self, Ordering};
+use std::path::{PathBuf};
 
 use clang_sys::*;
 
+use libc::{c_uint};
+#[cfg(feature="serde")]
+use serde::{Deserialize, Serialize};
+
 use utility;
-use super::{TranslationUnit};
+use super::{FixItError, TranslationUnit};
 use super::source::{SourceLocation, SourceRange};
 
 //================================================
@@ -41,10 +46,25 @@ pub enum FixIt<'tu> {
     Replacement(SourceRange<'tu>, String),
 }
 
+// FixItInfo _____________________________________
+
+/// An owned description of a suggested fix that does not borrow its translation unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub enum FixItInfo {
+    /// Delete a segment of the source file.
+    Deletion(RangeInfo),
+    /// Insert a string into the source file.
+    Insertion(LocationInfo, String),
+    /// Replace a segment of the source file with a string.
+    Replacement(RangeInfo, String),
+}
+
 // Severity ______________________________________
 
 /// Indicates the severity of a diagnostic.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub enum Severity {
     /// The diagnostic has been suppressed (e.g., by a command-line option).
@@ -126,6 +146,29 @@ impl<'tu> Diagnostic<'tu> {
         }
     }
 
+    /// Returns the command-line options that enable and disable this diagnostic, if any.
+    pub fn get_option(&self) -> Option<(String, String)> {
+        unsafe {
+            let mut disable = CXString::default();
+            let enable = clang_getDiagnosticOption(self.ptr, &mut disable);
+            let (enable, disable) = (utility::to_string_option(enable), utility::to_string(disable));
+            enable.map(|e| (e, disable))
+        }
+    }
+
+    /// Returns the number and name of the category of this diagnostic, if any.
+    pub fn get_category(&self) -> Option<(usize, String)> {
+        unsafe {
+            match clang_getDiagnosticCategory(self.ptr) {
+                0 => None,
+                n => {
+                    let text = utility::to_string(clang_getDiagnosticCategoryText(self.ptr));
+                    Some((n as usize, text))
+                },
+            }
+        }
+    }
+
     /// Returns the child diagnostics of this diagnostic.
     pub fn get_children(&self) -> Vec<Diagnostic> {
         let ptr = unsafe { clang_getChildDiagnostics(self.ptr) };
@@ -139,6 +182,27 @@ impl<'tu> Diagnostic<'tu> {
     pub fn formatter(&self) -> DiagnosticFormatter<'tu> {
         DiagnosticFormatter::new(*self)
     }
+
+    /// Returns an owned description of this diagnostic and its child diagnostics that does not
+    /// borrow the translation unit.
+    pub fn to_struct(&self) -> DiagnosticInfo {
+        let fix_its = self.get_fix_its().into_iter().map(|f| match f {
+            FixIt::Deletion(r) => FixItInfo::Deletion(RangeInfo::from_range(r)),
+            FixIt::Insertion(l, s) => FixItInfo::Insertion(LocationInfo::from_location(l), s),
+            FixIt::Replacement(r, s) => FixItInfo::Replacement(RangeInfo::from_range(r), s),
+        }).collect();
+
+        DiagnosticInfo {
+            severity: self.get_severity(),
+            text: self.get_text(),
+            location: LocationInfo::from_location(self.get_location()),
+            ranges: self.get_ranges().into_iter().map(RangeInfo::from_range).collect(),
+            fix_its,
+            option: self.get_option(),
+            category: self.get_category(),
+            children: self.get_children().iter().map(|c| c.to_struct()).collect(),
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -213,3 +277,280 @@ impl<'tu> DiagnosticFormatter<'tu> {
         unsafe { utility::to_string(clang_formatDiagnostic(self.diagnostic.ptr, self.flags)) }
     }
 }
+
+// DiagnosticInfo ________________________________
+
+/// An owned description of a diagnostic that does not borrow its translation unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub struct DiagnosticInfo {
+    /// The severity of the diagnostic.
+    pub severity: Severity,
+    /// The text of the diagnostic.
+    pub text: String,
+    /// The source location of the diagnostic.
+    pub location: LocationInfo,
+    /// The source ranges of the diagnostic.
+    pub ranges: Vec<RangeInfo>,
+    /// The fix-its for the diagnostic.
+    pub fix_its: Vec<FixItInfo>,
+    /// The command-line options that enable and disable the diagnostic, if any.
+    pub option: Option<(String, String)>,
+    /// The number and name of the category of the diagnostic, if any.
+    pub category: Option<(usize, String)>,
+    /// The child diagnostics of the diagnostic.
+    pub children: Vec<DiagnosticInfo>,
+}
+
+// DiagnosticSet _________________________________
+
+/// A set of diagnostics for a translation unit.
+pub struct DiagnosticSet<'tu> {
+    ptr: CXDiagnosticSet,
+    tu: &'tu TranslationUnit<'tu>,
+}
+
+impl<'tu> DiagnosticSet<'tu> {
+    //- Constructors -----------------------------
+
+    #[doc(hidden)]
+    pub fn from_ptr(ptr: CXDiagnosticSet, tu: &'tu TranslationUnit<'tu>) -> DiagnosticSet<'tu> {
+        assert!(!ptr.is_null());
+        DiagnosticSet { ptr, tu }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the number of top-level diagnostics in this diagnostic set.
+    pub fn len(&self) -> usize {
+        unsafe { clang_getNumDiagnosticsInSet(self.ptr) as usize }
+    }
+
+    /// Returns whether this diagnostic set contains no diagnostics.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the top-level diagnostic at the supplied index in this diagnostic set, if any.
+    ///
+    /// The notes attached to a diagnostic can be retrieved with `Diagnostic::get_children`.
+    pub fn get(&self, index: usize) -> Option<Diagnostic<'tu>> {
+        if index < self.len() {
+            let ptr = unsafe { clang_getDiagnosticInSet(self.ptr, index as c_uint) };
+            Some(Diagnostic::from_ptr(ptr, self.tu))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the top-level diagnostics in this diagnostic set.
+    pub fn get_diagnostics(&self) -> Vec<Diagnostic<'tu>> {
+        iter!(
+            clang_getNumDiagnosticsInSet(self.ptr),
+            clang_getDiagnosticInSet(self.ptr),
+        ).map(|d| Diagnostic::from_ptr(d, self.tu)).collect()
+    }
+}
+
+impl<'tu> Drop for DiagnosticSet<'tu> {
+    fn drop(&mut self) {
+        unsafe { clang_disposeDiagnosticSet(self.ptr); }
+    }
+}
+
+impl<'tu> fmt::Debug for DiagnosticSet<'tu> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("DiagnosticSet")
+            .field("diagnostics", &self.get_diagnostics())
+            .finish()
+    }
+}
+
+// LocationInfo __________________________________
+
+/// An owned description of a source location that does not borrow its translation unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub struct LocationInfo {
+    /// The path to the file of the source location, if it has any.
+    pub file: Option<PathBuf>,
+    /// The line of the source location.
+    pub line: u32,
+    /// The column of the source location.
+    pub column: u32,
+    /// The character offset of the source location.
+    pub offset: u32,
+}
+
+impl LocationInfo {
+    //- Constructors -----------------------------
+
+    fn from_location(location: SourceLocation) -> LocationInfo {
+        let location = location.get_spelling_location();
+        LocationInfo {
+            file: location.file.map(|f| f.get_path()),
+            line: location.line,
+            column: location.column,
+            offset: location.offset,
+        }
+    }
+}
+
+// RangeInfo _____________________________________
+
+/// An owned description of a source range that does not borrow its translation unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub struct RangeInfo {
+    /// The inclusive start of the source range.
+    pub start: LocationInfo,
+    /// The exclusive end of the source range.
+    pub end: LocationInfo,
+}
+
+impl RangeInfo {
+    //- Constructors -----------------------------
+
+    fn from_range(range: SourceRange) -> RangeInfo {
+        RangeInfo {
+            start: LocationInfo::from_location(range.get_start()),
+            end: LocationInfo::from_location(range.get_end()),
+        }
+    }
+}
+
+//================================================
+// Functions
+//================================================
+
+/// Returns the supplied source text with the supplied fix-its applied.
+///
+/// The fix-its are applied using the character offsets of the spelling locations of their source
+/// ranges, so they should all be in the file the source text was read from. The fix-its are
+/// sorted by source range before they are applied; insertions at the same location are applied
+/// in the order they are supplied.
+///
+/// # Failures
+///
+/// * a fix-it is not in a file or the fix-its are not all in the same file
+/// * two fix-its modify overlapping source ranges
+/// * a fix-it modifies a source range outside of the source text
+pub fn apply_fixits(source: &str, fixits: &[FixIt]) -> Result<String, FixItError> {
+    let mut file = None;
+    let mut edits = fixits.iter().map(|f| {
+        let (start, end, text) = match *f {
+            FixIt::Deletion(range) => (range.get_start(), range.get_end(), ""),
+            FixIt::Insertion(location, ref text) => (location, location, &text[..]),
+            FixIt::Replacement(range, ref text) => (range.get_start(), range.get_end(), &text[..]),
+        };
+        let (start, end) = (start.get_spelling_location(), end.get_spelling_location());
+        match (start.file, end.file) {
+            (Some(s), Some(e)) if s == e && file.unwrap_or(s) == s => file = Some(s),
+            _ => return Err(FixItError::File),
+        }
+        let (start, end) = (start.offset as usize, end.offset as usize);
+        if start > end || end > source.len() ||
+            !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+            return Err(FixItError::Range(start, end));
+        }
+        Ok((start, end, text))
+    }).collect::<Result<Vec<_>, _>>()?;
+    edits.sort_by_key(|e| (e.0, e.1));
+
+    for pair in edits.windows(2) {
+        if pair[1].0 < pair[0].1 {
+            return Err(FixItError::Overlap((pair[0].0, pair[0].1), (pair[1].0, pair[1].1)));
+        }
+    }
+
+    let mut text = String::with_capacity(source.len());
+    let mut offset = 0;
+    for (start, end, replacement) in edits {
+        text.push_str(&source[offset..start]);
+        text.push_str(replacement);
+        offset = end;
+    }
+    text.push_str(&source[offset..]);
+    Ok(text)
+}
+
+/// Writes the supplied diagnostic and its child diagnostics to the supplied buffer as a JSON
+/// object.
+pub(crate) fn write_json(diagnostic: &DiagnosticInfo, json: &mut String) {
+    let severity = match diagnostic.severity {
+        Severity::Ignored => "ignored",
+        Severity::Note => "note",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+        Severity::Fatal => "fatal error",
+    };
+    json.push_str("{\"severity\": ");
+    utility::push_json_string(json, severity);
+    json.push_str(", \"message\": ");
+    utility::push_json_string(json, &diagnostic.text);
+    json.push_str(", \"option\": ");
+    match diagnostic.option {
+        Some((ref enable, _)) => utility::push_json_string(json, enable),
+        None => json.push_str("null"),
+    }
+    json.push_str(", \"category\": ");
+    match diagnostic.category {
+        Some((_, ref name)) => utility::push_json_string(json, name),
+        None => json.push_str("null"),
+    }
+    json.push_str(", \"location\": ");
+    write_location_json(&diagnostic.location, json);
+
+    json.push_str(", \"ranges\": [");
+    for (index, range) in diagnostic.ranges.iter().enumerate() {
+        if index != 0 {
+            json.push_str(", ");
+        }
+        write_range_json(range, json);
+    }
+
+    json.push_str("], \"fixits\": [");
+    for (index, fix_it) in diagnostic.fix_its.iter().enumerate() {
+        if index != 0 {
+            json.push_str(", ");
+        }
+        let (start, end, text) = match *fix_it {
+            FixItInfo::Deletion(ref range) => (&range.start, &range.end, ""),
+            FixItInfo::Insertion(ref location, ref text) => (location, location, &text[..]),
+            FixItInfo::Replacement(ref range, ref text) => (&range.start, &range.end, &text[..]),
+        };
+        json.push_str("{\"start\": ");
+        write_location_json(start, json);
+        json.push_str(", \"finish\": ");
+        write_location_json(end, json);
+        json.push_str(", \"string\": ");
+        utility::push_json_string(json, text);
+        json.push('}');
+    }
+
+    json.push_str("], \"children\": [");
+    for (index, child) in diagnostic.children.iter().enumerate() {
+        if index != 0 {
+            json.push_str(", ");
+        }
+        write_json(child, json);
+    }
+    json.push_str("]}");
+}
+
+fn write_location_json(location: &LocationInfo, json: &mut String) {
+    json.push_str("{\"file\": ");
+    match location.file {
+        Some(ref file) => utility::push_json_string(json, &file.to_string_lossy()),
+        None => json.push_str("null"),
+    }
+    json.push_str(&format!(", \"line\": {}, \"column\": {}}}", location.line, location.column));
+}
+
+fn write_range_json(range: &RangeInfo, json: &mut String) {
+    json.push_str("{\"start\": ");
+    write_location_json(&range.start, json);
+    json.push_str(", \"finish\": ");
+    write_location_json(&range.end, json);
+    json.push('}');
+}
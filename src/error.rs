@@ -17,8 +17,9 @@ use std::fmt;
 
 use clang_sys::*;
 
-use libc::{c_longlong};
+use libc::{c_int, c_longlong};
 
+use diagnostic::{DiagnosticInfo, Severity};
 use utility::{FromError};
 
 //================================================
@@ -81,6 +82,102 @@ error! {
     }
 }
 
+// FixItError ____________________________________
+
+/// Indicates the error that prevented applying fix-its to source text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FixItError {
+    /// A fix-it is not in a file or the fix-its are not all in the same file.
+    File,
+    /// Two fix-its modify overlapping byte ranges (the contained byte ranges).
+    Overlap((usize, usize), (usize, usize)),
+    /// A fix-it modifies a byte range (the contained byte range) that is outside of the source text
+    /// or does not start and end on character boundaries.
+    Range(usize, usize),
+}
+
+impl Error for FixItError { }
+
+impl From<FixItError> for String {
+    fn from(error: FixItError) -> String {
+        error.to_string()
+    }
+}
+
+impl fmt::Display for FixItError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FixItError::File => write!(f, "the fix-its are not all in the same file"),
+            FixItError::Overlap((s1, e1), (s2, e2)) =>
+                write!(f, "the fix-its for bytes {}..{} and {}..{} overlap", s1, e1, s2, e2),
+            FixItError::Range(start, end) =>
+                write!(f, "the fix-it for bytes {}..{} is outside of the source text", start, end),
+        }
+    }
+}
+
+// LayoutMismatch ________________________________
+
+/// Indicates the first difference found between the actual and expected layout of a record type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LayoutMismatch {
+    /// The alignment of the record type could not be determined.
+    Alignof(AlignofError),
+    /// The offset of the field with the contained name could not be determined.
+    Offsetof(String, OffsetofError),
+    /// The size of the record type could not be determined.
+    Sizeof(SizeofError),
+    /// The alignment of the record type in bytes differs from the expected alignment.
+    Alignment {
+        /// The expected alignment in bytes.
+        expected: usize,
+        /// The actual alignment in bytes.
+        actual: usize,
+    },
+    /// The offset of a field in bits differs from the expected offset.
+    Offset {
+        /// The name of the field.
+        field: String,
+        /// The expected offset in bits.
+        expected: usize,
+        /// The actual offset in bits.
+        actual: usize,
+    },
+    /// The size of the record type in bytes differs from the expected size.
+    Size {
+        /// The expected size in bytes.
+        expected: usize,
+        /// The actual size in bytes.
+        actual: usize,
+    },
+}
+
+impl Error for LayoutMismatch { }
+
+impl From<LayoutMismatch> for String {
+    fn from(error: LayoutMismatch) -> String {
+        error.to_string()
+    }
+}
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LayoutMismatch::Alignof(error) => write!(f, "invalid alignment: {}", error),
+            LayoutMismatch::Offsetof(ref field, error) =>
+                write!(f, "invalid offset of field `{}`: {}", field, error),
+            LayoutMismatch::Sizeof(error) => write!(f, "invalid size: {}", error),
+            LayoutMismatch::Alignment { expected, actual } =>
+                write!(f, "expected an alignment of {} bytes, found {}", expected, actual),
+            LayoutMismatch::Offset { ref field, expected, actual } => write!(
+                f, "expected field `{}` at an offset of {} bits, found {}", field, expected, actual
+            ),
+            LayoutMismatch::Size { expected, actual } =>
+                write!(f, "expected a size of {} bytes, found {}", expected, actual),
+        }
+    }
+}
+
 // OffsetofError _________________________________
 
 error! {
@@ -99,6 +196,58 @@ error! {
     }
 }
 
+// ParseError ____________________________________
+
+/// Indicates the error that prevented the successful parsing of a translation unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParseError {
+    /// The translation unit could not be loaded.
+    Source(SourceError),
+    /// The translation unit was loaded but contains errors (the contained diagnostics are all of
+    /// the diagnostics for the translation unit, not just the errors).
+    Diagnostics(Vec<DiagnosticInfo>),
+}
+
+impl Error for ParseError { }
+
+impl From<ParseError> for String {
+    fn from(error: ParseError) -> String {
+        error.to_string()
+    }
+}
+
+impl From<SourceError> for ParseError {
+    fn from(error: SourceError) -> ParseError {
+        ParseError::Source(error)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::Source(error) => write!(f, "{}", error),
+            ParseError::Diagnostics(ref diagnostics) => {
+                let mut errors = diagnostics.iter().filter(|d| d.severity >= Severity::Error);
+                match errors.next() {
+                    Some(error) =>
+                        write!(f, "the translation unit contains errors: {}", error.text),
+                    None => write!(f, "the translation unit contains errors"),
+                }
+            },
+        }
+    }
+}
+
+// RewriteError __________________________________
+
+error! {
+    /// Indicates the error that prevented a rewriter from overwriting the changed source files.
+    pub enum RewriteError: c_int {
+        /// One or more of the changed source files could not be overwritten.
+        Write = (1, "one or more changed source files could not be overwritten"),
+    }
+}
+
 // SaveError _____________________________________
 
 error! {
@@ -22,6 +22,7 @@ extern crate clang_sys;
 extern crate libc;
 extern crate num_derive;
 extern crate num_traits;
+#[cfg(feature="serde")]
 extern crate serde;
 
 #[macro_use]
@@ -38,13 +39,15 @@ pub mod sonar;
 use std::cell::Cell;
 use std::cmp;
 use std::fmt;
+use std::fs;
 use std::hash;
+use std::iter;
 use std::mem;
 use std::ptr;
 use std::slice;
-use std::collections::{HashMap};
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
-use std::ffi::{CString};
+use std::ffi::{CString, OsString};
 use std::marker::{PhantomData};
 use std::path::{Path, PathBuf};
 
@@ -52,14 +55,16 @@ use clang_sys::*;
 
 use libc::{c_int, c_uint, c_ulong};
 
-use completion::{Completer, CompletionString};
-use diagnostic::{Diagnostic};
+use completion::{Completer, CompletionResult, CompletionString, MemberCompletions};
+use diagnostic::{Diagnostic, DiagnosticSet, Severity};
 use documentation::{Comment};
 use num_derive::FromPrimitive;
-use serde::Deserialize;
-use serde::Serialize;
-use source::{File, Module, SourceLocation, SourceRange};
-use token::{Token};
+#[cfg(feature="serde")]
+use serde::{Deserialize, Serialize};
+use source::{File, Inclusion, Module, SourceLocation, SourceRange};
+#[cfg(feature="clang_12_0")]
+use source::{Rewriter};
+use token::{Token, TokenKind};
 use utility::{FromError, Nullable};
 
 mod error;
@@ -96,6 +101,7 @@ impl Accessibility {
 
 /// Indicates the availability of an AST entity.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub enum Availability {
     /// The entity is available.
@@ -117,6 +123,130 @@ impl Availability {
     }
 }
 
+// BinaryOperatorKind ____________________________
+
+/// Indicates the operator of a binary operator expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub enum BinaryOperatorKind {
+    /// The `+` operator.
+    Add = 6,
+    /// The `+=` operator.
+    AddAssign = 26,
+    /// The `=` operator.
+    Assign = 22,
+    /// The `&` operator.
+    BitwiseAnd = 17,
+    /// The `&=` operator.
+    BitwiseAndAssign = 30,
+    /// The `|` operator.
+    BitwiseOr = 19,
+    /// The `|=` operator.
+    BitwiseOrAssign = 32,
+    /// The `^` operator.
+    BitwiseXor = 18,
+    /// The `^=` operator.
+    BitwiseXorAssign = 31,
+    /// The `,` operator.
+    Comma = 33,
+    /// The `/` operator.
+    Divide = 4,
+    /// The `/=` operator.
+    DivideAssign = 24,
+    /// The `==` operator.
+    Equal = 15,
+    /// The `>` operator.
+    GreaterThan = 12,
+    /// The `>=` operator.
+    GreaterThanOrEqual = 14,
+    /// The `<` operator.
+    LessThan = 11,
+    /// The `<=` operator.
+    LessThanOrEqual = 13,
+    /// The `&&` operator.
+    LogicalAnd = 20,
+    /// The `||` operator.
+    LogicalOr = 21,
+    /// The `*` operator.
+    Multiply = 3,
+    /// The `*=` operator.
+    MultiplyAssign = 23,
+    /// The `!=` operator.
+    NotEqual = 16,
+    /// The `->*` operator.
+    PointerToMemberArrow = 2,
+    /// The `.*` operator.
+    PointerToMemberDot = 1,
+    /// The `%` operator.
+    Remainder = 5,
+    /// The `%=` operator.
+    RemainderAssign = 25,
+    /// The `<<` operator.
+    ShiftLeft = 8,
+    /// The `<<=` operator.
+    ShiftLeftAssign = 28,
+    /// The `>>` operator.
+    ShiftRight = 9,
+    /// The `>>=` operator.
+    ShiftRightAssign = 29,
+    /// The `-` operator.
+    Subtract = 7,
+    /// The `-=` operator.
+    SubtractAssign = 27,
+    /// The `<=>` operator.
+    ThreeWayComparison = 10,
+}
+
+impl BinaryOperatorKind {
+    #[cfg(feature="clang_17_0")]
+    fn from_raw(raw: c_int) -> Option<Self> {
+        match raw {
+            1..=33 => Some(unsafe { mem::transmute::<c_int, BinaryOperatorKind>(raw) }),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(feature="clang_17_0"))]
+    fn from_spelling(spelling: &str) -> Option<Self> {
+        match spelling {
+            "+" => Some(BinaryOperatorKind::Add),
+            "+=" => Some(BinaryOperatorKind::AddAssign),
+            "=" => Some(BinaryOperatorKind::Assign),
+            "&" => Some(BinaryOperatorKind::BitwiseAnd),
+            "&=" => Some(BinaryOperatorKind::BitwiseAndAssign),
+            "|" => Some(BinaryOperatorKind::BitwiseOr),
+            "|=" => Some(BinaryOperatorKind::BitwiseOrAssign),
+            "^" => Some(BinaryOperatorKind::BitwiseXor),
+            "^=" => Some(BinaryOperatorKind::BitwiseXorAssign),
+            "," => Some(BinaryOperatorKind::Comma),
+            "/" => Some(BinaryOperatorKind::Divide),
+            "/=" => Some(BinaryOperatorKind::DivideAssign),
+            "==" => Some(BinaryOperatorKind::Equal),
+            ">" => Some(BinaryOperatorKind::GreaterThan),
+            ">=" => Some(BinaryOperatorKind::GreaterThanOrEqual),
+            "<" => Some(BinaryOperatorKind::LessThan),
+            "<=" => Some(BinaryOperatorKind::LessThanOrEqual),
+            "&&" => Some(BinaryOperatorKind::LogicalAnd),
+            "||" => Some(BinaryOperatorKind::LogicalOr),
+            "*" => Some(BinaryOperatorKind::Multiply),
+            "*=" => Some(BinaryOperatorKind::MultiplyAssign),
+            "!=" => Some(BinaryOperatorKind::NotEqual),
+            "->*" => Some(BinaryOperatorKind::PointerToMemberArrow),
+            ".*" => Some(BinaryOperatorKind::PointerToMemberDot),
+            "%" => Some(BinaryOperatorKind::Remainder),
+            "%=" => Some(BinaryOperatorKind::RemainderAssign),
+            "<<" => Some(BinaryOperatorKind::ShiftLeft),
+            "<<=" => Some(BinaryOperatorKind::ShiftLeftAssign),
+            ">>" => Some(BinaryOperatorKind::ShiftRight),
+            ">>=" => Some(BinaryOperatorKind::ShiftRightAssign),
+            "-" => Some(BinaryOperatorKind::Subtract),
+            "-=" => Some(BinaryOperatorKind::SubtractAssign),
+            "<=>" => Some(BinaryOperatorKind::ThreeWayComparison),
+            _ => None,
+        }
+    }
+}
+
 // CallingConvention _____________________________
 
 /// Indicates the calling convention specified for a function type.
@@ -177,10 +307,32 @@ impl CallingConvention {
     }
 }
 
+// ElaboratedKeyword _____________________________
+
+/// Indicates the keyword written in an elaborated type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ElaboratedKeyword {
+    /// The type was written with `class` (e.g., `class A`).
+    Class,
+    /// The type was written with `enum` (e.g., `enum E`).
+    Enum,
+    /// The type was written with `__interface` (e.g., `__interface I`).
+    Interface,
+    /// The type was written with `struct` (e.g., `struct S`).
+    Struct,
+    /// The type was written with `typename` (e.g., `typename T::U`).
+    Typename,
+    /// The type was written with `union` (e.g., `union U`).
+    Union,
+}
+
 // EntityKind ____________________________________
 
 /// Indicates the categorization of an AST entity.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+///
+/// Entity kinds are ordered by their underlying `libclang` values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub enum EntityKind {
     // IMPORTANT: If you add variants, update the from_raw() code below.
@@ -848,6 +1000,17 @@ pub enum EntityVisitResult {
     Recurse = 2,
 }
 
+// EnumValue _____________________________________
+
+/// The value of an enum constant.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EnumValue {
+    /// The value of an enum constant in an enum with a signed underlying type.
+    Signed(i64),
+    /// The value of an enum constant in an enum with an unsigned underlying type.
+    Unsigned(u64),
+}
+
 // EvaluationResult ______________________________
 
 /// The result of evaluating an expression.
@@ -914,6 +1077,20 @@ impl ExceptionSpecification {
     }
 }
 
+// HighlightKind _________________________________
+
+/// Indicates which part of a reference a source range returned by `Entity::highlight_ranges`
+/// covers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    /// The qualifier of the reference including the trailing `::` (e.g., `std::`).
+    Qualifier,
+    /// A piece of the name of the referenced AST entity (e.g., `vector`).
+    Name,
+    /// The template arguments of the reference (e.g., `<int>`).
+    TemplateArguments,
+}
+
 // Language ______________________________________
 
 /// Indicates the language used by a declaration.
@@ -1024,18 +1201,78 @@ pub enum Nullability {
     Nullable = 1,
     /// Whether values of this type can be null is (explicitly) unspecified.
     Unspecified = 2,
+    /// Values of this type can be null but only in the result of a successful operation (e.g., the
+    /// result parameter of an Objective-C completion handler).
+    ///
+    /// Only produced by `libclang` 12.0 and later.
+    NullableResult = 4,
 }
 
 #[cfg(feature="clang_8_0")]
 impl Nullability {
     fn from_raw(raw: c_int) -> Option<Self> {
         match raw {
-            0..=2 => Some(unsafe { mem::transmute(raw) }),
+            0..=2 | 4 => Some(unsafe { mem::transmute(raw) }),
             _ => None,
         }
     }
 }
 
+// Primitive _____________________________________
+
+/// Indicates a builtin C or C++ type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    /// `bool` or `_Bool`
+    Bool,
+    /// `char` (regardless of whether `char` is signed for the target)
+    Char,
+    /// `char16_t`
+    Char16,
+    /// `char32_t`
+    Char32,
+    /// `double`
+    Double,
+    /// `float`
+    Float,
+    /// `__float128`
+    Float128,
+    /// `_Float16`
+    Float16,
+    /// `__fp16`
+    Half,
+    /// `int`
+    Int,
+    /// `__int128_t`
+    Int128,
+    /// `long`
+    Long,
+    /// `long double`
+    LongDouble,
+    /// `long long`
+    LongLong,
+    /// `signed char`
+    SChar,
+    /// `short`
+    Short,
+    /// `unsigned char`
+    UChar,
+    /// `unsigned int`
+    UInt,
+    /// `__uint128_t`
+    UInt128,
+    /// `unsigned long`
+    ULong,
+    /// `unsigned long long`
+    ULongLong,
+    /// `unsigned short`
+    UShort,
+    /// `void`
+    Void,
+    /// `wchar_t`
+    WChar,
+}
+
 // PrintingPolicyFlag ____________________________
 
 /// Flags for the printing policy.
@@ -1046,6 +1283,10 @@ pub enum PrintingPolicyFlag {
     /// Whether to suppress printing specifiers for a given type or declaration.
     SuppressSpecifiers = 1,
     /// Whether to suppress printing the tag keyword.
+    ///
+    /// This only applies to types written without a tag keyword (e.g., `S` rather than
+    /// `struct S`). Since `libclang` 16.0, such types are also printed without a tag keyword
+    /// regardless of this flag.
     SuppressTagKeyword = 2,
     /// Whether to include the body of a tag definition.
     IncludeTagDefinition = 3,
@@ -1205,7 +1446,8 @@ impl TlsKind {
 // TypeKind ______________________________________
 
 /// Indicates the categorization of a type.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, FromPrimitive)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, FromPrimitive)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub enum TypeKind {
     /// A type whose specific kind is not exposed via this interface.
@@ -1589,6 +1831,73 @@ impl TypeKind {
     }
 }
 
+// UnaryOperatorKind _____________________________
+
+/// Indicates the operator of a unary operator expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub enum UnaryOperatorKind {
+    /// The `&` operator.
+    AddressOf = 5,
+    /// The `~` operator.
+    BitwiseNot = 9,
+    /// The `co_await` operator.
+    Coawait = 14,
+    /// The `*` operator.
+    Dereference = 6,
+    /// The `__extension__` operator.
+    Extension = 13,
+    /// The `__imag` operator.
+    Imaginary = 12,
+    /// The `!` operator.
+    LogicalNot = 10,
+    /// The `-` operator.
+    Minus = 8,
+    /// The `+` operator.
+    Plus = 7,
+    /// The postfix `--` operator.
+    PostDecrement = 2,
+    /// The postfix `++` operator.
+    PostIncrement = 1,
+    /// The prefix `--` operator.
+    PreDecrement = 4,
+    /// The prefix `++` operator.
+    PreIncrement = 3,
+    /// The `__real` operator.
+    Real = 11,
+}
+
+impl UnaryOperatorKind {
+    #[cfg(feature="clang_17_0")]
+    fn from_raw(raw: c_int) -> Option<Self> {
+        match raw {
+            1..=14 => Some(unsafe { mem::transmute::<c_int, UnaryOperatorKind>(raw) }),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(feature="clang_17_0"))]
+    fn from_spelling(spelling: &str, prefix: bool) -> Option<Self> {
+        match (spelling, prefix) {
+            ("++", false) => Some(UnaryOperatorKind::PostIncrement),
+            ("--", false) => Some(UnaryOperatorKind::PostDecrement),
+            ("++", true) => Some(UnaryOperatorKind::PreIncrement),
+            ("--", true) => Some(UnaryOperatorKind::PreDecrement),
+            ("&", true) => Some(UnaryOperatorKind::AddressOf),
+            ("*", true) => Some(UnaryOperatorKind::Dereference),
+            ("+", true) => Some(UnaryOperatorKind::Plus),
+            ("-", true) => Some(UnaryOperatorKind::Minus),
+            ("~", true) => Some(UnaryOperatorKind::BitwiseNot),
+            ("!", true) => Some(UnaryOperatorKind::LogicalNot),
+            ("__real", true) | ("__real__", true) => Some(UnaryOperatorKind::Real),
+            ("__imag", true) | ("__imag__", true) => Some(UnaryOperatorKind::Imaginary),
+            ("__extension__", true) => Some(UnaryOperatorKind::Extension),
+            ("co_await", true) => Some(UnaryOperatorKind::Coawait),
+            _ => None,
+        }
+    }
+}
+
 // Visibility ____________________________________
 
 /// Indicates the linker visibility of an AST element.
@@ -1624,6 +1933,7 @@ type PhantomUnsendUnsync = PhantomData<*mut ()>;
 
 thread_local! {
   static CLANG_ACTIVE: Cell<bool> = Cell::new(false);
+  static UNUSED_UNSAVED_HOOK: Cell<Option<fn(&Path)>> = Cell::new(None);
 }
 
 /// An empty type which prevents the use of this library from multiple threads simultaneously.
@@ -1670,6 +1980,18 @@ impl Clang {
             unsend_unsync: PhantomData,
         })
     }
+
+    //- Mutators ---------------------------------
+
+    /// Sets the function which is called on this thread with the path of each unsaved file that
+    /// was ignored while parsing, reparsing, or running code completion and returns the previous
+    /// function, if any.
+    ///
+    /// See `TranslationUnit::get_unused_unsaved` for when an unsaved file is ignored. No function
+    /// is set by default and the function is unset when this `Clang` instance is dropped.
+    pub fn set_unused_unsaved_hook(&self, hook: Option<fn(&Path)>) -> Option<fn(&Path)> {
+        UNUSED_UNSAVED_HOOK.with(|h| h.replace(hook))
+    }
 }
 
 impl Drop for Clang {
@@ -1677,6 +1999,7 @@ impl Drop for Clang {
         CLANG_ACTIVE.with(|clang_active| {
             clang_active.set(false);
         });
+        UNUSED_UNSAVED_HOOK.with(|h| h.set(None));
 
         #[cfg(feature = "runtime")]
         {
@@ -1755,12 +2078,56 @@ impl CompileCommands {
 
     /// Returns all commands for this search
     pub fn get_commands(&self) -> Vec<CompileCommand> {
-        iter!(
-            clang_CompileCommands_getSize(self.ptr),
-            clang_CompileCommands_getCommand(self.ptr),
-        )
-        .map(|p| CompileCommand::from_ptr(self, p))
-        .collect()
+        self.iter().collect()
+    }
+
+    /// Returns the number of commands for this search.
+    pub fn len(&self) -> usize {
+        unsafe { clang_CompileCommands_getSize(self.ptr) as usize }
+    }
+
+    /// Returns whether there are no commands for this search.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the command with the supplied index, if any.
+    pub fn get(&self, index: usize) -> Option<CompileCommand<'_>> {
+        if index < self.len() {
+            let ptr = unsafe { clang_CompileCommands_getCommand(self.ptr, index as c_uint) };
+            Some(CompileCommand::from_ptr(self, ptr))
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the commands for this search.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item=CompileCommand<'_>> + '_ {
+        (0..self.len()).map(move |i| {
+            let ptr = unsafe { clang_CompileCommands_getCommand(self.ptr, i as c_uint) };
+            CompileCommand::from_ptr(self, ptr)
+        })
+    }
+}
+
+impl<'cmds> IntoIterator for &'cmds CompileCommands {
+    type Item = CompileCommand<'cmds>;
+    type IntoIter = Box<dyn ExactSizeIterator<Item=CompileCommand<'cmds>> + 'cmds>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
+    }
+}
+
+impl IntoIterator for CompileCommands {
+    type Item = CompileCommandInfo;
+    type IntoIter = CompileCommandsIntoIter;
+
+    /// Returns an iterator over owned copies of the commands for this search.
+    ///
+    /// The underlying commands are disposed of when the iterator is dropped.
+    fn into_iter(self) -> CompileCommandsIntoIter {
+        CompileCommandsIntoIter { commands: self, index: 0 }
     }
 }
 
@@ -1809,9 +2176,55 @@ impl<'cmds> CompileCommand<'cmds> {
         .collect()
     }
 
+    /// Returns an owned copy of this command.
+    pub fn to_info(&self) -> CompileCommandInfo {
+        CompileCommandInfo {
+            directory: self.get_directory(),
+            #[cfg(feature="clang_3_8")]
+            filename: self.get_filename(),
+            arguments: self.get_arguments(),
+        }
+    }
+
     // TODO: Args, mapped source path, mapped sourth context.
 }
 
+/// An owned compile command from CompilationDatabase.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CompileCommandInfo {
+    /// The working directory where the command was executed.
+    pub directory: PathBuf,
+    /// The filename associated with the command.
+    #[cfg(feature="clang_3_8")]
+    pub filename: PathBuf,
+    /// The arguments passed to the command.
+    pub arguments: Vec<String>,
+}
+
+/// An iterator over owned copies of the commands in a `CompileCommands`.
+#[derive(Debug)]
+pub struct CompileCommandsIntoIter {
+    commands: CompileCommands,
+    index: usize,
+}
+
+impl Iterator for CompileCommandsIntoIter {
+    type Item = CompileCommandInfo;
+
+    fn next(&mut self) -> Option<CompileCommandInfo> {
+        let command = self.commands.get(self.index)?.to_info();
+        self.index += 1;
+        Some(command)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.commands.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for CompileCommandsIntoIter { }
+
 // Entity ________________________________________
 
 /// An AST entity.
@@ -1831,6 +2244,11 @@ impl<'tu> Entity<'tu> {
     //- Accessors --------------------------------
 
     /// Evaluates this AST entity, if possible.
+    ///
+    /// Expressions and variable declarations with initializers can be evaluated, while other
+    /// declarations (e.g., enum constant declarations) return `None` and must be evaluated through
+    /// their initializer expressions. The evaluation result returned by `libclang` is always
+    /// disposed.
     #[cfg(feature="clang_3_9")]
     pub fn evaluate(&self) -> Option<EvaluationResult> {
         macro_rules! string {
@@ -1891,6 +2309,28 @@ impl<'tu> Entity<'tu> {
         unsafe { PrettyPrinter::from_raw(clang_getCursorPrintingPolicy(self.raw), self) }
     }
 
+    /// Returns the pretty printed source code for this declaration using the default printing
+    /// policy for this declaration.
+    #[cfg(feature="clang_7_0")]
+    pub fn pretty_print_default(&self) -> String {
+        self.get_pretty_printer().print()
+    }
+
+    /// Returns the pretty printed source code for this declaration followed by the pretty printed
+    /// source code for each of its member declarations, each on its own line.
+    ///
+    /// Enabling `PrintingPolicyFlag::UseTerseOutput` on the supplied pretty printer will omit the
+    /// bodies of this declaration and its members, which is useful for summarizing a class.
+    #[cfg(feature="clang_7_0")]
+    pub fn pretty_print_members(&self, printer: &PrettyPrinter) -> String {
+        let print = |e: &Entity| unsafe {
+            utility::to_string(clang_getCursorPrettyPrinted(e.raw, printer.ptr))
+        };
+        let mut lines = vec![print(self)];
+        lines.extend(self.get_children().iter().filter(|c| c.is_declaration()).map(print));
+        lines.join("\n")
+    }
+
     /// Returns the source location of this AST entity, if any.
     pub fn get_location(&self) -> Option<SourceLocation<'tu>> {
         unsafe { clang_getCursorLocation(self.raw).map(|l| SourceLocation::from_raw(l, self.tu)) }
@@ -1901,6 +2341,19 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_getCursorExtent(self.raw).map(|r| SourceRange::from_raw(r, self.tu)) }
     }
 
+    /// Returns the source range of the name of this AST entity and the source range of this AST
+    /// entity, if both are available.
+    ///
+    /// The name source range is the first of the source ranges returned by `get_name_ranges`
+    /// (e.g., the first selector piece of an Objective-C method).
+    pub fn get_ranges(&self) -> Option<(SourceRange<'tu>, SourceRange<'tu>)> {
+        unsafe {
+            let name = clang_Cursor_getSpellingNameRange(self.raw, 0, 0);
+            let name = name.map(|r| SourceRange::from_raw(r, self.tu))?;
+            Some((name, self.get_range()?))
+        }
+    }
+
     /// Returns the accessibility of this declaration or base class specifier, if applicable.
     pub fn get_accessibility(&self) -> Option<Accessibility> {
         unsafe {
@@ -1920,10 +2373,43 @@ impl<'tu> Entity<'tu> {
     }
 
     /// Returns the availability of this AST entity.
+    ///
+    /// This is the availability of the declaration in the current translation unit (e.g., whether
+    /// it has been marked as deprecated or unavailable). See `get_platform_availability` for the
+    /// availability of a declaration on specific platforms.
     pub fn get_availability(&self) -> Availability {
         Availability::from_raw(unsafe {clang_getCursorAvailability(self.raw) }).unwrap()
     }
 
+    /// Returns the operator of this binary operator expression, if applicable.
+    ///
+    /// For `libclang` 17.0 and later, the operator is provided by `libclang`. Otherwise, the
+    /// operator is determined by inspecting the first token that follows the left operand of this
+    /// expression and `None` is returned if the left operand starts or ends inside a macro
+    /// expansion (since the tokens in the source file may not correspond to the expression).
+    pub fn get_binary_operator_kind(&self) -> Option<BinaryOperatorKind> {
+        match self.get_kind() {
+            EntityKind::BinaryOperator | EntityKind::CompoundAssignOperator => { },
+            _ => return None,
+        }
+
+        #[cfg(feature="clang_17_0")]
+        {
+            BinaryOperatorKind::from_raw(unsafe { clang_getCursorBinaryOperatorKind(self.raw) })
+        }
+
+        #[cfg(not(feature="clang_17_0"))]
+        {
+            let left = self.get_children().first()?.get_range()?;
+            get_file_offset(left.get_start())?;
+            let end = get_file_offset(left.get_end())?;
+            let token = self.get_range()?.tokenize().into_iter().find(|t| {
+                t.get_location().get_expansion_location().offset >= end
+            })?;
+            BinaryOperatorKind::from_spelling(&token.get_spelling())
+        }
+    }
+
     /// Returns the width of this bit field, if applicable.
     pub fn get_bit_field_width(&self) -> Option<usize> {
         unsafe {
@@ -1936,34 +2422,129 @@ impl<'tu> Entity<'tu> {
         }
     }
 
+    /// Returns the source text of the body of this function definition, if applicable.
+    ///
+    /// The returned text includes the enclosing braces of the body.
+    #[cfg(feature="clang_6_0")]
+    pub fn get_body_text(&self) -> Option<String> {
+        let children = self.get_children();
+        let body = children.iter().rev().find(|c| c.get_kind() == EntityKind::CompoundStmt)?;
+        body.get_range()?.get_text()
+    }
+
     /// Returns the canonical entity for this AST entity.
     ///
     /// In the C family of languages, some types of entities can be declared multiple times. When
     /// there are multiple declarations of the same entity, only one will be considered canonical.
+    ///
+    /// If this AST entity is not a declaration (or is the only declaration of an entity), this AST
+    /// entity is returned, so this never returns a null cursor.
     pub fn get_canonical_entity(&self) -> Entity<'tu> {
         unsafe { Entity::from_raw(clang_getCanonicalCursor(self.raw), self.tu) }
     }
 
     /// Returns the comment associated with this AST entity, if any.
     pub fn get_comment(&self) -> Option<String> {
+        if !is_comment_api_loaded() {
+            return None;
+        }
+
         unsafe { utility::to_string_option(clang_Cursor_getRawCommentText(self.raw)) }
     }
 
     ///  Returns the parsed comment associated with this declaration, if applicable.
     pub fn get_parsed_comment(&self) -> Option<Comment<'tu>> {
+        if !is_comment_api_loaded() {
+            return None;
+        }
+
         unsafe { clang_Cursor_getParsedComment(self.raw).map(Comment::from_raw) }
     }
 
     /// Returns the brief of the comment associated with this AST entity, if any.
     pub fn get_comment_brief(&self) -> Option<String> {
+        if !is_comment_api_loaded() {
+            return None;
+        }
+
         unsafe { utility::to_string_option(clang_Cursor_getBriefCommentText(self.raw)) }
     }
 
     /// Returns the source range of the comment associated with this AST entity, if any.
     pub fn get_comment_range(&self) -> Option<SourceRange<'tu>> {
+        if !is_comment_api_loaded() {
+            return None;
+        }
+
         unsafe { clang_Cursor_getCommentRange(self.raw).map(|r| SourceRange::from_raw(r, self.tu)) }
     }
 
+    /// Returns code completion results for the members of the type declared by this struct, class,
+    /// or union declaration, if applicable.
+    ///
+    /// If possible, code completion is run at the end of a synthetic function appended to the
+    /// contents of the main file of the translation unit which accesses a member through a pointer
+    /// to the type (e.g., `A *o; o->`). The contents of the main file are taken from the supplied
+    /// unsaved files if present and otherwise read from disk. The modified contents are supplied as
+    /// an unsaved file along with the other supplied unsaved files, so the translation unit and the
+    /// file on disk are not modified. The code completion context, container, and diagnostics of
+    /// the results describe the synthetic member access rather than this declaration.
+    ///
+    /// If the contents of the main file are not available or the type is anonymous (and therefore
+    /// cannot be named in the synthetic function), the results are instead synthesized from the
+    /// completion strings of the fields, methods, and static members declared in the definition of
+    /// the type. These results do not include inherited members and have no code completion context
+    /// or availability filtering.
+    ///
+    /// `None` is returned if this AST entity is not a struct, class, or union declaration or if the
+    /// type has no definition in this translation unit.
+    pub fn complete_members(&self, unsaved: &[Unsaved]) -> Option<MemberCompletions<'tu>> {
+        match self.get_kind() {
+            EntityKind::ClassDecl | EntityKind::StructDecl | EntityKind::UnionDecl => { },
+            _ => return None,
+        }
+
+        let definition = self.get_definition()?;
+        let name = self.get_type()?.get_display_name();
+        let file = self.tu.get_entity().get_name()?;
+
+        let path = utility::from_path(&file);
+        let contents = match unsaved.iter().find(|u| u.path == path) {
+            Some(unsaved) => Some(unsaved.contents.to_string_lossy().into_owned()),
+            None => fs::read_to_string(&file).ok(),
+        };
+
+        let mut contents = match contents {
+            Some(contents) if !name.contains('(') => contents,
+            _ => {
+                let results = definition.get_children().into_iter().filter_map(|c| {
+                    match c.get_kind() {
+                        EntityKind::FieldDecl | EntityKind::VarDecl | EntityKind::Method |
+                        EntityKind::FunctionTemplate | EntityKind::ConversionFunction |
+                        EntityKind::Destructor => { },
+                        _ => return None,
+                    }
+
+                    let string = unsafe { clang_getCursorCompletionString(c.raw) };
+                    let string = string.map(CompletionString::from_ptr)?;
+                    Some(CompletionResult { kind: c.get_kind(), string })
+                }).collect();
+                return Some(MemberCompletions::Declared(results));
+            },
+        };
+
+        contents.push_str("\nvoid clang_rs_complete_members(");
+        contents.push_str(&name);
+        contents.push_str(" *clang_rs_object) { clang_rs_object->");
+
+        let line = contents.lines().count() as u32;
+        let column = contents.lines().last().map_or(0, |l| l.len()) as u32 + 1;
+        let mut unsaved = unsaved.iter().filter(|u| u.path != path).cloned().collect::<Vec<_>>();
+        unsaved.push(Unsaved::new(&file, &contents));
+        let results = self.tu.completer(file, line, column).unsaved(&unsaved).complete();
+        Some(MemberCompletions::Completed(results))
+    }
+
     /// Returns a completion string for this declaration or macro definition, if applicable.
     pub fn get_completion_string(&self) -> Option<CompletionString> {
         unsafe { clang_getCursorCompletionString(self.raw).map(CompletionString::from_ptr) }
@@ -1994,6 +2575,19 @@ impl<'tu> Entity<'tu> {
         children
     }
 
+    /// Returns an iterator over the children of this AST entity.
+    ///
+    /// The children are not collected until the first call to `next`, after which the iterator
+    /// yields them from a buffer (so adapters such as `find` can short-circuit without further
+    /// calls into `libclang`).
+    pub fn children_iter(&self) -> impl Iterator<Item=Entity<'tu>> {
+        let entity = *self;
+        let mut children = None;
+        iter::from_fn(move || {
+            children.get_or_insert_with(|| entity.get_children().into_iter()).next()
+        })
+    }
+
     /// Returns the AST entity that describes the definition of this AST entity, if any.
     pub fn get_definition(&self) -> Option<Entity<'tu>> {
         unsafe { clang_getCursorDefinition(self.raw).map(|p| Entity::from_raw(p, self.tu)) }
@@ -2012,6 +2606,28 @@ impl<'tu> Entity<'tu> {
         }
     }
 
+    /// Returns the names and values of the constants in this enum declaration, if applicable.
+    ///
+    /// The values are signed or unsigned according to the underlying type of this enum declaration.
+    /// Constants without an initializer have the value `libclang` computed for them (e.g., one
+    /// more than the value of the previous constant).
+    pub fn get_enum_constants(&self) -> Vec<(String, EnumValue)> {
+        let unsigned = match self.get_enum_underlying_type() {
+            Some(type_) => type_.get_canonical_type().is_unsigned_integer(),
+            None => return vec![],
+        };
+
+        self.get_children().into_iter().filter_map(|c| {
+            let (signed, unsigned_) = c.get_enum_constant_value()?;
+            let value = if unsigned {
+                EnumValue::Unsigned(unsigned_)
+            } else {
+                EnumValue::Signed(signed)
+            };
+            Some((c.get_name()?, value))
+        }).collect()
+    }
+
     /// Returns the underlying type of this enum declaration, if applicable.
     pub fn get_enum_underlying_type(&self) -> Option<Type<'tu>> {
         unsafe { clang_getEnumDeclIntegerType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
@@ -2078,6 +2694,10 @@ impl<'tu> Entity<'tu> {
     }
 
     /// Returns the mangled name of this AST entity, if any.
+    ///
+    /// This is the primary mangling of this AST entity, which is the only mangling for most
+    /// declarations. C++ constructors and destructors may have several manglings (e.g., complete
+    /// and base object variants), all of which are returned by `get_mangled_names`.
     #[cfg(feature="clang_3_6")]
     pub fn get_mangled_name(&self) -> Option<String> {
         unsafe { utility::to_string_option(clang_Cursor_getMangling(self.raw)) }
@@ -2090,11 +2710,60 @@ impl<'tu> Entity<'tu> {
     }
 
     /// Returns the mangled names of this Objective-C class interface or implementation, if applicable.
+    ///
+    /// `libclang` only produces these for Objective-C class interfaces and implementations (e.g.,
+    /// `_OBJC_CLASS_$_A` and `_OBJC_METACLASS_$_A`), so `None` is returned for any other AST entity
+    /// including Objective-C methods. The string set returned by `libclang` is always disposed.
     #[cfg(feature="clang_6_0")]
     pub fn get_mangled_objc_names(&self) -> Option<Vec<String>> {
         unsafe { utility::to_string_set_option(clang_Cursor_getObjCManglings(self.raw)) }
     }
 
+    /// Returns the body of this macro definition, if applicable.
+    ///
+    /// The body is reconstructed from the tokens that follow the name (and the parameter list, for
+    /// a function-like macro) of this macro definition.
+    pub fn get_macro_body(&self) -> Option<String> {
+        self.get_macro_parts().map(|(_, body)| token::join(&body))
+    }
+
+    /// Returns the parameters of this function-like macro definition, if applicable.
+    ///
+    /// The last parameter of a variadic macro definition will be `...` (or `name...` for a GNU
+    /// named variadic parameter).
+    pub fn get_macro_parameters(&self) -> Option<Vec<String>> {
+        self.get_macro_parts().and_then(|(parameters, _)| parameters)
+    }
+
+    fn get_macro_parts(&self) -> Option<(Option<Vec<String>>, Vec<Token<'tu>>)> {
+        if self.get_kind() != EntityKind::MacroDefinition {
+            return None;
+        }
+
+        let range = self.get_range()?;
+        let end = range.get_end().get_spelling_location().offset;
+        let tokens = range.tokenize().into_iter().filter(|t| {
+            t.get_location().get_spelling_location().offset < end
+        }).collect::<Vec<_>>();
+
+        // A macro definition is function-like if its name is immediately followed by `(`.
+        let name = tokens.first()?.get_range().get_end().get_spelling_location().offset;
+        let function = match tokens.get(1) {
+            Some(t) => t.get_spelling() == "(" && t.get_location().get_spelling_location().offset == name,
+            None => false,
+        };
+
+        if !function {
+            return Some((None, tokens[1..].to_vec()));
+        }
+
+        let close = tokens.iter().position(|t| t.get_spelling() == ")")?;
+        let parameters = tokens[2..close].split(|t| t.get_spelling() == ",").filter(|p| {
+            !p.is_empty()
+        }).map(|p| p.iter().map(|t| t.get_spelling()).collect()).collect();
+        Some((Some(parameters), tokens[close + 1..].to_vec()))
+    }
+
     /// Returns the module imported by this module import declaration, if applicable.
     pub fn get_module(&self) -> Option<Module<'tu>> {
         unsafe { clang_Cursor_getModule(self.raw).map(|m| Module::from_ptr(m, self.tu)) }
@@ -2105,6 +2774,14 @@ impl<'tu> Entity<'tu> {
         unsafe { utility::to_string_option(clang_getCursorSpelling(self.raw)) }
     }
 
+    /// Replaces the contents of the supplied buffer with the name of this AST entity, leaving the
+    /// buffer empty if this AST entity has no name.
+    ///
+    /// This avoids allocating a new `String` for every AST entity when the buffer is reused.
+    pub fn get_name_into(&self, buffer: &mut String) {
+        unsafe { utility::to_string_into(clang_getCursorSpelling(self.raw), buffer) }
+    }
+
     /// Returns the source ranges of the name of this AST entity.
     pub fn get_name_ranges(&self) -> Vec<SourceRange<'tu>> {
         unsafe {
@@ -2122,6 +2799,24 @@ impl<'tu> Entity<'tu> {
         }
     }
 
+    /// Returns the names of the namespaces that enclose this AST entity, outermost first.
+    ///
+    /// Only the semantic parents of this AST entity are considered (so a namespace entity does not
+    /// include its own name). Anonymous namespaces are skipped while inline namespaces are included
+    /// (e.g., `std::__1` for `std::__1::vector`). Enclosing records and functions are ignored.
+    pub fn get_namespace_path(&self) -> Vec<String> {
+        let mut path = vec![];
+        let mut entity = self.get_semantic_parent();
+        while let Some(parent) = entity {
+            if parent.get_kind() == EntityKind::Namespace {
+                path.extend(parent.get_name());
+            }
+            entity = parent.get_semantic_parent();
+        }
+        path.reverse();
+        path
+    }
+
     /// Returns which attributes were applied to this Objective-C property, if applicable.
     pub fn get_objc_attributes(&self) -> Option<ObjCAttributes> {
         let attributes = unsafe { clang_Cursor_getObjCPropertyAttributes(self.raw, 0) };
@@ -2158,12 +2853,69 @@ impl<'tu> Entity<'tu> {
         }
     }
 
+    /// Returns the number of pieces in the selector of this Objective-C method declaration or
+    /// message expression (e.g., `2` for `setX:y:`).
+    ///
+    /// Returns `1` for other named AST entities and `0` for other unnamed AST entities. The source
+    /// range of each selector piece is available through `get_name_ranges`.
+    pub fn get_objc_selector_piece_count(&self) -> usize {
+        match self.get_kind() {
+            EntityKind::ObjCClassMethodDecl |
+            EntityKind::ObjCInstanceMethodDecl |
+            EntityKind::ObjCMessageExpr => unsafe {
+                (0..).take_while(|i| {
+                    clang_Range_isNull(clang_Cursor_getSpellingNameRange(self.raw, *i, 0)) == 0
+                }).count()
+            },
+            _ => if self.get_name().is_some() { 1 } else { 0 },
+        }
+    }
+
+    /// Returns the keywords of the selector of this Objective-C method declaration or message
+    /// expression along with their source ranges (e.g., `setX` and `y` for `setX:y:`).
+    ///
+    /// Returns an empty `Vec` for other AST entities.
+    pub fn get_objc_selector_pieces(&self) -> Vec<(String, SourceRange<'tu>)> {
+        match self.get_kind() {
+            EntityKind::ObjCClassMethodDecl |
+            EntityKind::ObjCInstanceMethodDecl |
+            EntityKind::ObjCMessageExpr => {
+                let name = self.get_name().unwrap_or_default();
+                name.split(':').map(|p| p.into()).zip(self.get_name_ranges()).collect()
+            },
+            _ => vec![],
+        }
+    }
+
     /// Returns the name of the method implementing the setter for this Objective-C property, if applicable
     #[cfg(feature="clang_8_0")]
     pub fn get_objc_setter_name(&self) -> Option<String> {
         utility::to_string_option(unsafe { clang_Cursor_getObjCPropertySetterName(self.raw) })
     }
 
+    /// Returns the name of the method implementing the getter for this Objective-C property if it
+    /// is not the default name (e.g., `isEnabled` for `@property (getter=isEnabled) BOOL enabled`).
+    ///
+    /// Unlike `get_objc_getter_name`, this returns `None` when the getter has the default name.
+    #[cfg(feature="clang_8_0")]
+    pub fn get_objc_property_getter_name(&self) -> Option<String> {
+        let name = self.get_name()?;
+        self.get_objc_getter_name().filter(|g| *g != name)
+    }
+
+    /// Returns the name of the method implementing the setter for this Objective-C property if it
+    /// is not the default name (e.g., `setOn:` for `@property (setter=setOn:) BOOL enabled`).
+    ///
+    /// Unlike `get_objc_setter_name`, this returns `None` when the setter has the default name.
+    #[cfg(feature="clang_8_0")]
+    pub fn get_objc_property_setter_name(&self) -> Option<String> {
+        let name = self.get_name()?;
+        let mut characters = name.chars();
+        let first = characters.next()?.to_uppercase().collect::<String>();
+        let default = format!("set{}{}:", first, characters.as_str());
+        self.get_objc_setter_name().filter(|s| *s != default)
+    }
+
     /// Returns the type encoding for this Objective-C declaration, if applicable.
     pub fn get_objc_type_encoding(&self) -> Option<String> {
         unsafe { utility::to_string_option(clang_getDeclObjCTypeEncoding(self.raw)) }
@@ -2244,6 +2996,70 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_getCursorReferenced(self.raw).map(|p| Entity::from_raw(p, self.tu)) }
     }
 
+    /// Returns the source ranges of the name of the AST entity referred to by this AST entity.
+    ///
+    /// The returned ranges may optionally include the qualifier (e.g., `std::`) and the template
+    /// arguments (e.g., `<int>`) of the reference.
+    pub fn get_reference_name_ranges(
+        &self, qualifier: bool, template_arguments: bool
+    ) -> Vec<SourceRange<'tu>> {
+        let mut flags = 0;
+        if qualifier {
+            flags |= CXNameRange_WantQualifier;
+        }
+        if template_arguments {
+            flags |= CXNameRange_WantTemplateArgs;
+        }
+        unsafe {
+            (0..).map(|i| clang_getCursorReferenceNameRange(self.raw, flags, i)).take_while(|r| {
+                clang_Range_isNull(*r) == 0
+            }).map(|r| SourceRange::from_raw(r, self.tu)).collect()
+        }
+    }
+
+    /// Returns the source ranges of the pieces of this reference to an AST entity along with the
+    /// parts of the reference they cover, in source order.
+    ///
+    /// Each piece of the name of the referenced AST entity (there may be more than one, e.g., for
+    /// Objective-C selectors) is returned as a `HighlightKind::Name` source range, preceded by its
+    /// qualifier and followed by its template arguments if the reference has them (e.g., `std::`,
+    /// `vector`, and `<int>` for `std::vector<int>`).
+    pub fn highlight_ranges(&self) -> Vec<(SourceRange<'tu>, HighlightKind)> {
+        let range = |flags, index| unsafe {
+            let range = clang_getCursorReferenceNameRange(self.raw, flags, index);
+            if clang_Range_isNull(range) == 0 {
+                Some(SourceRange::from_raw(range, self.tu))
+            } else {
+                None
+            }
+        };
+
+        let mut ranges = vec![];
+        for index in 0.. {
+            let name = match range(0, index) {
+                Some(name) => name,
+                None => break,
+            };
+            let (start, end) = (name.get_start(), name.get_end());
+            let offset = |l: SourceLocation| l.get_spelling_location().offset;
+
+            if let Some(qualified) = range(CXNameRange_WantQualifier, index) {
+                if offset(qualified.get_start()) < offset(start) {
+                    let qualifier = SourceRange::new(qualified.get_start(), start);
+                    ranges.push((qualifier, HighlightKind::Qualifier));
+                }
+            }
+            ranges.push((name, HighlightKind::Name));
+            if let Some(templated) = range(CXNameRange_WantTemplateArgs, index) {
+                if offset(templated.get_end()) > offset(end) {
+                    let arguments = SourceRange::new(end, templated.get_end());
+                    ranges.push((arguments, HighlightKind::TemplateArguments));
+                }
+            }
+        }
+        ranges
+    }
+
     /// Returns the semantic parent of this AST entity, if any.
     pub fn get_semantic_parent(&self) -> Option<Entity<'tu>> {
         let parent = unsafe { clang_getCursorSemanticParent(self.raw) };
@@ -2269,6 +3085,9 @@ impl<'tu> Entity<'tu> {
     }
 
     /// Returns the template arguments for this template function specialization, if applicable.
+    ///
+    /// Implicit specializations (e.g., the AST entity referenced by `f<3>` in a call) are supported
+    /// as well as explicit specializations.
     #[cfg(feature="clang_3_6")]
     pub fn get_template_arguments(&self) -> Option<Vec<TemplateArgument<'tu>>> {
         let get_type = &clang_Cursor_getTemplateArgumentType;
@@ -2331,15 +3150,71 @@ impl<'tu> Entity<'tu> {
     }
 
     /// Returns the type of this AST entity, if any.
+    ///
+    /// For typedef declarations, this is the typedef type itself (e.g., `I` for `typedef int I;`)
+    /// rather than the type it aliases. Use `get_typedef_underlying_type` to get the aliased type
+    /// or `Type::get_canonical_type` to resolve all typedefs.
     pub fn get_type(&self) -> Option<Type<'tu>> {
         unsafe { clang_getCursorType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
     }
 
+    /// Returns the typedef type declared by this typedef declaration (e.g., `I` for
+    /// `typedef int I;`), if applicable.
+    ///
+    /// Unlike `get_type`, this returns `None` for AST entities which are not typedef or type alias
+    /// declarations.
+    pub fn get_typedef_type(&self) -> Option<Type<'tu>> {
+        match self.get_kind() {
+            EntityKind::TypedefDecl | EntityKind::TypeAliasDecl => self.get_type(),
+            _ => None,
+        }
+    }
+
     /// Returns the underlying type of this typedef declaration, if applicable.
+    ///
+    /// This is the type aliased by the typedef declaration (e.g., `int` for `typedef int I;`),
+    /// which may itself be a typedef type.
     pub fn get_typedef_underlying_type(&self) -> Option<Type<'tu>> {
         unsafe { clang_getTypedefDeclUnderlyingType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
     }
 
+    /// Returns the operator of this unary operator expression, if applicable.
+    ///
+    /// For `libclang` 17.0 and later, the operator is provided by `libclang`. Otherwise, the
+    /// operator is determined by inspecting the token that precedes or follows the operand of this
+    /// expression and `None` is returned if this expression or its operand starts or ends inside a
+    /// macro expansion (since the tokens in the source file may not correspond to the expression).
+    pub fn get_unary_operator_kind(&self) -> Option<UnaryOperatorKind> {
+        if self.get_kind() != EntityKind::UnaryOperator {
+            return None;
+        }
+
+        #[cfg(feature="clang_17_0")]
+        {
+            UnaryOperatorKind::from_raw(unsafe { clang_getCursorUnaryOperatorKind(self.raw) })
+        }
+
+        #[cfg(not(feature="clang_17_0"))]
+        {
+            let range = self.get_range()?;
+            get_file_offset(range.get_start())?;
+            get_file_offset(range.get_end())?;
+            let operand = self.get_children().first()?.get_range()?;
+            let start = get_file_offset(operand.get_start())?;
+            let end = get_file_offset(operand.get_end())?;
+
+            let tokens = range.tokenize();
+            let offset = |t: &Token| t.get_location().get_expansion_location().offset;
+            let first = tokens.first()?;
+            if offset(first) < start {
+                UnaryOperatorKind::from_spelling(&first.get_spelling(), true)
+            } else {
+                let last = tokens.iter().find(|t| offset(t) >= end)?;
+                UnaryOperatorKind::from_spelling(&last.get_spelling(), false)
+            }
+        }
+    }
+
     /// Returns the USR for this AST entity, if any.
     pub fn get_usr(&self) -> Option<Usr> {
         unsafe { utility::to_string_option(clang_getCursorUSR(self.raw)).map(Usr) }
@@ -2411,7 +3286,12 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_CXXMethod_isConst(self.raw) != 0 }
     }
 
-    /// Returns whether this AST entity is a C++ converting constructor.
+    /// Returns whether this AST entity is a C++ conversion operator.
+    pub fn is_conversion_operator(&self) -> bool {
+        self.get_kind() == EntityKind::ConversionFunction
+    }
+
+    /// Returns whether this AST entity is a C++ converting constructor.
     #[cfg(feature="clang_3_9")]
     pub fn is_converting_constructor(&self) -> bool {
         unsafe { clang_CXXConstructor_isConvertingConstructor(self.raw) != 0 }
@@ -2449,6 +3329,31 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_Cursor_isDynamicCall(self.raw) != 0 }
     }
 
+    /// Returns whether this AST entity is an explicit C++ constructor or conversion operator.
+    #[cfg(feature="clang_17_0")]
+    pub fn is_explicit(&self) -> bool {
+        unsafe { clang_CXXMethod_isExplicit(self.raw) != 0 }
+    }
+
+    /// Returns whether this AST entity is a forward declaration of a record or enum.
+    ///
+    /// An entity is considered a forward declaration when all of the following hold:
+    ///
+    /// * its kind is `ClassDecl`, `EnumDecl`, `StructDecl`, or `UnionDecl`,
+    /// * it is not a definition (see `is_definition`), and
+    /// * its definition (see `get_definition`) is either unavailable or a different entity.
+    ///
+    /// For example, `struct Foo;` is a forward declaration whether or not `struct Foo {};` appears
+    /// elsewhere in the translation unit, but `struct Foo {};` is not.
+    pub fn is_forward_declaration(&self) -> bool {
+        match self.get_kind() {
+            EntityKind::ClassDecl | EntityKind::EnumDecl |
+            EntityKind::StructDecl | EntityKind::UnionDecl => { },
+            _ => return false,
+        }
+        !self.is_definition() && self.get_definition() != Some(*self)
+    }
+
     /// Returns whether this AST entity is a function-like macro.
     #[cfg(feature="clang_3_9")]
     pub fn is_function_like_macro(&self) -> bool {
@@ -2481,6 +3386,9 @@ impl<'tu> Entity<'tu> {
 
     /// Returns whether this AST entity is an Objective-C method or property declaration with the
     /// `@optional` attribute applied to it.
+    ///
+    /// This includes all of the methods and properties that follow an `@optional` directive in an
+    /// Objective-C protocol until the next `@required` directive.
     pub fn is_objc_optional(&self) -> bool {
         unsafe { clang_Cursor_isObjCOptional(self.raw) != 0 }
     }
@@ -2516,6 +3424,134 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_CXXMethod_isVirtual(self.raw) != 0 }
     }
 
+    /// Returns whether this function parameter declaration has a default argument.
+    ///
+    /// `libclang` does not expose default arguments directly, so this is determined heuristically
+    /// by searching for an expression child which follows an `=` token that is not nested in
+    /// brackets or parentheses (which excludes expressions such as array sizes).
+    pub fn parameter_has_default(&self) -> bool {
+        if self.get_kind() != EntityKind::ParmDecl {
+            return false;
+        }
+
+        let tokens = match self.get_range() {
+            Some(range) => range.tokenize(),
+            None => return false,
+        };
+
+        let mut depth = 0i32;
+        let equals = tokens.iter().find(|t| {
+            match &t.get_spelling()[..] {
+                "(" | "[" | "{" | "<" => depth += 1,
+                ")" | "]" | "}" | ">" => depth -= 1,
+                ">>" => depth -= 2,
+                "=" => return depth == 0,
+                _ => { },
+            }
+            false
+        });
+
+        let equals = match equals {
+            Some(equals) => equals.get_location().get_file_location().offset,
+            None => return false,
+        };
+
+        let children = self.get_children();
+        let expressions = children.iter().filter(|c| c.is_expression());
+        expressions.filter_map(|c| c.get_range()).any(|r| {
+            r.get_start().get_file_location().offset > equals
+        })
+    }
+
+    /// Returns a key which identifies the signature of this AST entity.
+    ///
+    /// The key consists of the fully-qualified name of this AST entity followed by, for functions
+    /// and methods, the canonical parameter types and the `const` and ref qualifiers (e.g.,
+    /// `ns::A::f(int, const char *) const &`). Redeclarations of a function have the same key
+    /// while different overloads of a function have different keys.
+    pub fn signature_key(&self) -> String {
+        let mut names = vec![];
+        let mut entity = Some(*self);
+        while let Some(parent) = entity {
+            if parent.get_kind() == EntityKind::TranslationUnit {
+                break;
+            }
+            names.push(parent.get_name().unwrap_or_else(|| "(anonymous)".into()));
+            entity = parent.get_semantic_parent();
+        }
+        names.reverse();
+        let mut key = names.join("::");
+
+        let type_ = match self.get_type() {
+            Some(type_) => type_,
+            None => return key,
+        };
+
+        if let Some(arguments) = type_.get_argument_types() {
+            let mut arguments = arguments.iter().map(|a| {
+                a.get_canonical_type().get_display_name()
+            }).collect::<Vec<_>>();
+            if type_.is_variadic() {
+                arguments.push("...".into());
+            }
+            key.push('(');
+            key.push_str(&arguments.join(", "));
+            key.push(')');
+
+            if self.is_const_method() {
+                key.push_str(" const");
+            }
+
+            match type_.get_ref_qualifier() {
+                Some(RefQualifier::LValue) => key.push_str(" &"),
+                Some(RefQualifier::RValue) => key.push_str(" &&"),
+                None => { },
+            }
+        }
+
+        key
+    }
+
+    /// Compares the layout of the type of this record declaration with the supplied layout.
+    ///
+    /// The expected size and alignment are in bytes and the expected field offsets are supplied as
+    /// pairs of field names and offsets in bytes. Fields which are not supplied are not checked.
+    ///
+    /// # Failures
+    ///
+    /// * the layout of the type could not be determined
+    /// * the actual size, alignment, or field offsets differ from those supplied
+    pub fn verify_layout(
+        &self, expected_size: usize, expected_align: usize, fields: &[(&str, usize)]
+    ) -> Result<(), LayoutMismatch> {
+        let type_ = match self.get_type() {
+            Some(type_) => type_,
+            None => return Err(LayoutMismatch::Sizeof(SizeofError::Incomplete)),
+        };
+
+        let size = type_.get_sizeof().map_err(LayoutMismatch::Sizeof)?;
+        if size != expected_size {
+            return Err(LayoutMismatch::Size { expected: expected_size, actual: size });
+        }
+
+        let align = type_.get_alignof().map_err(LayoutMismatch::Alignof)?;
+        if align != expected_align {
+            return Err(LayoutMismatch::Alignment { expected: expected_align, actual: align });
+        }
+
+        for &(field, offset) in fields {
+            let actual = type_.get_offsetof(field).map_err(|e| {
+                LayoutMismatch::Offsetof(field.into(), e)
+            })?;
+            if actual != offset * 8 {
+                let field = field.into();
+                return Err(LayoutMismatch::Offset { field, expected: offset * 8, actual });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Visits the children of this AST entity recursively and returns whether visitation was ended
     /// by the callback returning `EntityVisitResult::Break`.
     ///
@@ -2565,16 +3601,63 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_isDeclaration(self.raw.kind) != 0 }
     }
 
+    /// Returns whether this AST entity is an enum declaration.
+    pub fn is_enum(&self) -> bool {
+        self.get_kind() == EntityKind::EnumDecl
+    }
+
     /// Returns whether this AST entity is categorized as an expression.
     pub fn is_expression(&self) -> bool {
         unsafe { clang_isExpression(self.raw.kind) != 0 }
     }
 
+    /// Returns whether this AST entity is a field declaration (i.e., a `FieldDecl` or an
+    /// `ObjCIvarDecl`).
+    pub fn is_field(&self) -> bool {
+        matches!(self.get_kind(), EntityKind::FieldDecl | EntityKind::ObjCIvarDecl)
+    }
+
+    /// Returns whether this AST entity is a function declaration or function template (i.e., a
+    /// `FunctionDecl` or a `FunctionTemplate`).
+    ///
+    /// Methods are not included (see `is_method`) unless they are templates since method templates
+    /// are also `FunctionTemplate`s.
+    pub fn is_function(&self) -> bool {
+        matches!(self.get_kind(), EntityKind::FunctionDecl | EntityKind::FunctionTemplate)
+    }
+
+    /// Returns whether this AST entity is a method declaration (i.e., a `Method`, `Constructor`,
+    /// `Destructor`, `ConversionFunction`, `ObjCInstanceMethodDecl`, or `ObjCClassMethodDecl`).
+    pub fn is_method(&self) -> bool {
+        matches!(
+            self.get_kind(),
+            EntityKind::Method | EntityKind::Constructor | EntityKind::Destructor |
+            EntityKind::ConversionFunction | EntityKind::ObjCInstanceMethodDecl |
+            EntityKind::ObjCClassMethodDecl
+        )
+    }
+
+    /// Returns whether this AST entity is a namespace declaration.
+    pub fn is_namespace(&self) -> bool {
+        self.get_kind() == EntityKind::Namespace
+    }
+
     /// Returns whether this AST entity is categorized as a preprocessing entity.
     pub fn is_preprocessing(&self) -> bool {
         unsafe { clang_isPreprocessing(self.raw.kind) != 0 }
     }
 
+    /// Returns whether this AST entity is a record declaration or record template (i.e., a
+    /// `StructDecl`, `ClassDecl`, `UnionDecl`, `ClassTemplate`, or
+    /// `ClassTemplatePartialSpecialization`).
+    pub fn is_record(&self) -> bool {
+        matches!(
+            self.get_kind(),
+            EntityKind::StructDecl | EntityKind::ClassDecl | EntityKind::UnionDecl |
+            EntityKind::ClassTemplate | EntityKind::ClassTemplatePartialSpecialization
+        )
+    }
+
     /// Returns whether this AST entity is categorized as a reference.
     pub fn is_reference(&self) -> bool {
         unsafe { clang_isReference(self.raw.kind) != 0 }
@@ -2585,11 +3668,25 @@ impl<'tu> Entity<'tu> {
         unsafe { clang_isStatement(self.raw.kind) != 0 }
     }
 
+    /// Returns whether this AST entity is a typedef or type alias declaration (i.e., a
+    /// `TypedefDecl`, `TypeAliasDecl`, or `TypeAliasTemplateDecl`).
+    pub fn is_typedef(&self) -> bool {
+        matches!(
+            self.get_kind(),
+            EntityKind::TypedefDecl | EntityKind::TypeAliasDecl | EntityKind::TypeAliasTemplateDecl
+        )
+    }
+
     /// Returns whether the categorization of this AST entity is unexposed.
     pub fn is_unexposed(&self) -> bool {
         unsafe { clang_isUnexposed(self.raw.kind) != 0 }
     }
 
+    /// Returns whether this AST entity is a variable declaration.
+    pub fn is_variable(&self) -> bool {
+        self.get_kind() == EntityKind::VarDecl
+    }
+
     //- Location ---------------------------------
 
     /// Returns whether this AST entity is in a main file.
@@ -2601,6 +3698,16 @@ impl<'tu> Entity<'tu> {
     pub fn is_in_system_header(&self) -> bool {
         self.get_range().map_or(false, |r| r.is_in_system_header())
     }
+
+    /// Returns a key that orders AST entities by their spelling locations, if this AST entity has
+    /// a location in a file.
+    ///
+    /// The key consists of the unique ID of the file (see `File::get_id`) and the character offset
+    /// of the location in that file.
+    pub fn source_order_key(&self) -> Option<((u64, u64, u64), u32)> {
+        let location = self.get_location()?.get_spelling_location();
+        location.file.map(|f| (f.get_id(), location.offset))
+    }
 }
 
 impl<'tu> fmt::Debug for Entity<'tu> {
@@ -2646,6 +3753,22 @@ pub struct ExternalSymbol {
     pub generated: bool,
 }
 
+// FieldLayout ___________________________________
+
+/// The layout of a field in a record type.
+#[cfg(feature="clang_3_7")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldLayout<'tu> {
+    /// The name of the field, if it is not anonymous.
+    pub name: Option<String>,
+    /// The offset of the field in bits.
+    pub offset: usize,
+    /// The type of the field.
+    pub type_: Type<'tu>,
+    /// The width of the field in bits, if it is a bit field.
+    pub bit_field_width: Option<usize>,
+}
+
 // Index _________________________________________
 
 /// A collection of translation units.
@@ -2711,6 +3834,26 @@ impl<'c> fmt::Debug for Index<'c> {
     }
 }
 
+// MacroInfo _____________________________________
+
+/// A macro definition in a translation unit.
+#[cfg(feature="clang_3_9")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MacroInfo<'tu> {
+    /// The name of the macro.
+    pub name: String,
+    /// Whether the macro is function-like.
+    pub is_function_like: bool,
+    /// Whether the macro is a builtin macro (e.g., `__LINE__`).
+    pub is_builtin: bool,
+    /// The parameters of the macro, if it is function-like.
+    pub parameters: Option<Vec<String>>,
+    /// The body of the macro.
+    pub body: String,
+    /// The source location of the definition of the macro, if it has any.
+    pub location: Option<SourceLocation<'tu>>,
+}
+
 // ObjCAttributes ________________________________
 
 options! {
@@ -2774,6 +3917,8 @@ builder! {
         index: &'tu Index<'tu>,
         file: PathBuf,
         arguments: Vec<CString>,
+        target_triple: Option<CString>,
+        sysroot: Option<CString>,
         unsaved: Vec<Unsaved>;
     OPTIONS:
         /// Sets whether certain code completion results will be cached when the translation unit is
@@ -2825,7 +3970,9 @@ impl<'tu> Parser<'tu> {
 
     fn new<F: Into<PathBuf>>(index: &'tu Index<'tu>, file: F) -> Parser<'tu> {
         let flags: CXTranslationUnit_Flags = 0;
-        Parser { index, file: file.into(), arguments: vec![], unsaved: vec![], flags }
+        let file = file.into();
+        let (target_triple, sysroot) = (None, None);
+        Parser { index, file, arguments: vec![], target_triple, sysroot, unsaved: vec![], flags }
     }
 
     //- Mutators ---------------------------------
@@ -2844,7 +3991,36 @@ impl<'tu> Parser<'tu> {
         self
     }
 
+    /// Sets the target triple to compile for (e.g., `x86_64-unknown-linux-gnu`).
+    ///
+    /// This is equivalent to supplying `-target <triple>` as compiler arguments and is preserved
+    /// when the compiler arguments are set with `arguments`.
+    ///
+    /// # Panics
+    ///
+    /// * `triple` is empty or contains only whitespace
+    pub fn target_triple<T: AsRef<str>>(&mut self, triple: T) -> &mut Parser<'tu> {
+        let triple = triple.as_ref().trim();
+        assert!(!triple.is_empty(), "target triple must not be empty");
+        self.target_triple = Some(utility::from_string(triple));
+        self
+    }
+
+    /// Sets the directory to use as the logical root directory for headers and libraries.
+    ///
+    /// This is equivalent to supplying `--sysroot=<sysroot>` as a compiler argument and is
+    /// preserved when the compiler arguments are set with `arguments`.
+    pub fn sysroot<P: AsRef<Path>>(&mut self, sysroot: P) -> &mut Parser<'tu> {
+        let mut argument = OsString::from("--sysroot=");
+        argument.push(sysroot.as_ref());
+        self.sysroot = Some(utility::from_path(argument));
+        self
+    }
+
     /// Sets the unsaved files to use.
+    ///
+    /// Unsaved files that are ignored are reported to the hook set by
+    /// `Clang::set_unused_unsaved_hook` (see `TranslationUnit::get_unused_unsaved`).
     pub fn unsaved(&mut self, unsaved: &[Unsaved]) -> &mut Parser<'tu> {
         self.unsaved = unsaved.into();
         self
@@ -2860,7 +4036,14 @@ impl<'tu> Parser<'tu> {
     /// * `libclang` crashes
     /// * an unknown error occurs
     pub fn parse(&self) -> Result<TranslationUnit<'tu>, SourceError> {
-        let arguments = self.arguments.iter().map(|a| a.as_ptr()).collect::<Vec<_>>();
+        let target = utility::from_string("-target");
+        let mut arguments = self.arguments.iter().map(|a| a.as_ptr()).collect::<Vec<_>>();
+        if let Some(ref triple) = self.target_triple {
+            arguments.extend_from_slice(&[target.as_ptr(), triple.as_ptr()]);
+        }
+        if let Some(ref sysroot) = self.sysroot {
+            arguments.push(sysroot.as_ptr());
+        }
         let unsaved = self.unsaved.iter().map(|u| u.as_raw()).collect::<Vec<_>>();
         unsafe {
             let mut ptr = ptr::null_mut();
@@ -2874,7 +4057,31 @@ impl<'tu> Parser<'tu> {
                 self.flags,
                 &mut ptr,
             );
-            SourceError::from_error(code).map(|_| TranslationUnit::from_ptr(ptr))
+            SourceError::from_error(code)?;
+            let tu = TranslationUnit::from_ptr(ptr);
+            tu.report_unused_unsaved(&self.unsaved);
+            Ok(tu)
+        }
+    }
+
+    /// Parses a translation unit and checks that it does not contain any errors.
+    ///
+    /// The diagnostics for a successfully parsed translation unit are available through
+    /// `TranslationUnit::get_diagnostics`.
+    ///
+    /// # Failures
+    ///
+    /// * an error occurs while deserializing an AST file
+    /// * `libclang` crashes
+    /// * an unknown error occurs
+    /// * the translation unit contains error or fatal diagnostics
+    pub fn parse_checked(&self) -> Result<TranslationUnit<'tu>, ParseError> {
+        let tu = self.parse()?;
+        if !tu.has_errors() {
+            Ok(tu)
+        } else {
+            let diagnostics = tu.get_diagnostics().iter().map(|d| d.to_struct()).collect();
+            Err(ParseError::Diagnostics(diagnostics))
         }
     }
 }
@@ -2971,6 +4178,42 @@ impl<'e> Drop for PrettyPrinter<'e> {
     }
 }
 
+// RecordLayout __________________________________
+
+/// The layout of a record type.
+#[cfg(feature="clang_3_7")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordLayout<'tu> {
+    /// The size of the record type in bytes.
+    pub size: usize,
+    /// The alignment of the record type in bytes.
+    pub alignment: usize,
+    /// The layouts of the fields in the record type, in declaration order.
+    pub fields: Vec<FieldLayout<'tu>>,
+}
+
+// SymbolRecord __________________________________
+
+/// An owned description of a declaration suitable for a symbol index.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
+pub struct SymbolRecord {
+    /// The USR of the declaration.
+    pub usr: Usr,
+    /// The name of the declaration, if it has any.
+    pub name: Option<String>,
+    /// The categorization of the declaration.
+    pub kind: EntityKind,
+    /// The path to the file that contains the declaration, if it has any.
+    pub file: Option<PathBuf>,
+    /// The line of the declaration.
+    pub line: u32,
+    /// The column of the declaration.
+    pub column: u32,
+    /// Whether the declaration is a definition.
+    pub is_definition: bool,
+}
+
 // Target ________________________________________
 
 /// Information about the target for a translation unit.
@@ -3050,18 +4293,438 @@ impl<'i> TranslationUnit<'i> {
         }).collect()
     }
 
+    /// Returns whether the `libclang` functions used to retrieve comments are available.
+    ///
+    /// If this returns `false`, the comment accessors on `Entity` (e.g., `Entity::get_comment`)
+    /// will always return `None`.
+    pub fn comments_available(&self) -> bool {
+        is_comment_api_loaded()
+    }
+
+    /// Returns the diagnostic set for this translation unit.
+    pub fn get_diagnostic_set(&'i self) -> DiagnosticSet<'i> {
+        unsafe { DiagnosticSet::from_ptr(clang_getDiagnosticSetFromTU(self.ptr), self) }
+    }
+
+    /// Returns the diagnostics for this translation unit with a severity at or above the supplied
+    /// severity.
+    pub fn get_diagnostics_filtered(&'i self, severity: Severity) -> Vec<Diagnostic<'i>> {
+        iter!(clang_getNumDiagnostics(self.ptr), clang_getDiagnostic(self.ptr),).filter(|d| {
+            unsafe { clang_getDiagnosticSeverity(*d) >= severity as CXDiagnosticSeverity }
+        }).map(|d| Diagnostic::from_ptr(d, self)).collect()
+    }
+
+    /// Returns the diagnostics for this translation unit sorted by descending severity and then by
+    /// source location.
+    ///
+    /// Diagnostics with the same severity are ordered by the path of the file they are in and then
+    /// by their character offsets in that file. Diagnostics that are not in a file (e.g., those
+    /// produced for command-line arguments) come first.
+    pub fn get_diagnostics_sorted(&'i self) -> Vec<Diagnostic<'i>> {
+        let mut diagnostics = self.get_diagnostics();
+        diagnostics.sort_by_cached_key(|d| {
+            let location = d.get_location().get_file_location();
+            let path = location.file.map(|f| f.get_path());
+            (cmp::Reverse(d.get_severity()), path, location.offset)
+        });
+        diagnostics
+    }
+
+    /// Returns whether this translation unit has any error or fatal diagnostics.
+    ///
+    /// The severities of the diagnostics are checked without constructing a `Diagnostic` for each
+    /// one, stopping at the first error or fatal diagnostic.
+    pub fn has_errors(&self) -> bool {
+        iter!(clang_getNumDiagnostics(self.ptr), clang_getDiagnostic(self.ptr),).any(|d| {
+            unsafe { clang_getDiagnosticSeverity(d) >= CXDiagnostic_Error }
+        })
+    }
+
+    /// Returns one declaration for each distinct record type (i.e., class, struct, or union) in
+    /// this translation unit.
+    ///
+    /// Record declarations are deduplicated by their canonical types, so forward declarations and
+    /// redeclarations of a record are collapsed. The definition of a record is returned if it has
+    /// one, otherwise the first declaration is returned. Anonymous records are distinct types and
+    /// are therefore always included. Declarations are returned in the order they first appear.
+    pub fn collect_unique_records(&'i self) -> Vec<Entity<'i>> {
+        let mut seen = HashSet::new();
+        let kinds = [EntityKind::ClassDecl, EntityKind::StructDecl, EntityKind::UnionDecl];
+        self.get_entities(|e| kinds.contains(&e.get_kind())).into_iter().filter_map(|e| {
+            let canonical = e.get_type()?.get_canonical_type().get_declaration()?;
+            if seen.insert(canonical.get_canonical_entity()) {
+                Some(e.get_definition().unwrap_or(e))
+            } else {
+                None
+            }
+        }).collect()
+    }
+
+    /// Returns a symbol record for each declaration in this translation unit that has a USR.
+    ///
+    /// Every declaration is visited recursively (as in `get_entities`) and the file locations of
+    /// the declarations are used. Declarations are returned in the order they are visited.
+    pub fn collect_symbol_records(&'i self) -> Vec<SymbolRecord> {
+        let mut records = vec![];
+        self.get_entity().visit_children(|e, _| {
+            if let (true, Some(usr)) = (e.is_declaration(), e.get_usr()) {
+                let location = e.get_location().map(|l| l.get_file_location());
+                records.push(SymbolRecord {
+                    usr,
+                    name: e.get_name(),
+                    kind: e.get_kind(),
+                    file: location.and_then(|l| l.file).map(|f| f.get_path()),
+                    line: location.map_or(0, |l| l.line),
+                    column: location.map_or(0, |l| l.column),
+                    is_definition: e.is_definition(),
+                });
+            }
+            EntityVisitResult::Recurse
+        });
+        records
+    }
+
+    /// Returns the public declarations in the main file of this translation unit along with
+    /// whether each declaration has a documentation comment.
+    ///
+    /// Declarations are collected from the top level of the main file and from within named
+    /// namespaces, classes, structs, unions, and enums. Unnamed declarations and private or
+    /// protected members are skipped.
+    pub fn documentation_coverage(&'i self) -> Vec<(Entity<'i>, bool)> {
+        let mut entities = vec![];
+        self.get_entity().visit_children(|e, _| {
+            if !e.is_declaration() || !e.is_in_main_file() || e.get_name().is_none() {
+                return EntityVisitResult::Continue;
+            }
+
+            match e.get_accessibility() {
+                Some(Accessibility::Private) | Some(Accessibility::Protected) => {
+                    return EntityVisitResult::Continue;
+                },
+                _ => { },
+            }
+
+            entities.push((e, e.get_comment_range().is_some()));
+
+            match e.get_kind() {
+                EntityKind::Namespace | EntityKind::ClassDecl | EntityKind::StructDecl |
+                EntityKind::UnionDecl | EntityKind::EnumDecl | EntityKind::ClassTemplate => {
+                    EntityVisitResult::Recurse
+                },
+                _ => EntityVisitResult::Continue,
+            }
+        });
+        entities
+    }
+
+    /// Returns the diagnostics for this translation unit with source locations in the main file.
+    pub fn get_main_file_diagnostics(&'i self) -> Vec<Diagnostic<'i>> {
+        iter!(clang_getNumDiagnostics(self.ptr), clang_getDiagnostic(self.ptr),).filter(|d| {
+            unsafe { clang_Location_isFromMainFile(clang_getDiagnosticLocation(*d)) != 0 }
+        }).map(|d| Diagnostic::from_ptr(d, self)).collect()
+    }
+
     /// Returns the entity for this translation unit.
     pub fn get_entity(&'i self) -> Entity<'i> {
         unsafe { Entity::from_raw(clang_getTranslationUnitCursor(self.ptr), self) }
     }
 
+    /// Returns the declarations in this translation unit which satisfy the supplied predicate.
+    ///
+    /// Every declaration in this translation unit is visited recursively, so this can be combined
+    /// with `Entity::is_in_main_file` or `Entity::is_in_system_header` to collect the declarations
+    /// that appear in user code (e.g., `tu.get_entities(|e| !e.is_in_system_header())`).
+    pub fn get_entities<F: FnMut(&Entity<'i>) -> bool>(&'i self, mut include: F) -> Vec<Entity<'i>> {
+        let mut entities = vec![];
+        self.get_entity().visit_children(|e, _| {
+            if e.is_declaration() && include(&e) {
+                entities.push(e);
+            }
+            EntityVisitResult::Recurse
+        });
+        entities
+    }
+
+    /// Returns the declarations in this translation unit which satisfy the supplied predicate
+    /// along with their pretty printed source code.
+    ///
+    /// The declarations are collected as in `get_entities` and are all printed with the printing
+    /// policy of the supplied pretty printer, regardless of the declaration it was retrieved from.
+    #[cfg(feature="clang_7_0")]
+    pub fn pretty_print_all<F: FnMut(&Entity<'i>) -> bool>(
+        &'i self, printer: &PrettyPrinter, include: F
+    ) -> Vec<(Entity<'i>, String)> {
+        self.get_entities(include).into_iter().map(|e| {
+            let string = unsafe {
+                utility::to_string(clang_getCursorPrettyPrinted(e.raw, printer.ptr))
+            };
+            (e, string)
+        }).collect()
+    }
+
     /// Returns the file at the supplied path in this translation unit, if any.
     pub fn get_file<F: AsRef<Path>>(&'i self, file: F) -> Option<File<'i>> {
         let file = unsafe { clang_getFile(self.ptr, utility::from_path(file).as_ptr()) };
         file.map(|f| File::from_ptr(f, self))
     }
 
+    /// Returns the files included in this translation unit along with their inclusion stacks.
+    ///
+    /// The inclusion stack of a file contains the locations of the inclusion directives that led to
+    /// that file being included, starting with the inclusion directive in the file which directly
+    /// included it. The inclusion stack of the main file is empty.
+    pub fn get_inclusions(&'i self) -> Vec<(File<'i>, Vec<SourceLocation<'i>>)> {
+        extern "C" fn visit(
+            file: CXFile, stack: *mut CXSourceLocation, length: c_uint, data: CXClientData
+        ) {
+            unsafe {
+                let &mut (tu, ref mut inclusions) = &mut *(data as *mut (
+                    &TranslationUnit, Vec<(File, Vec<SourceLocation>)>
+                ));
+
+                let stack = if length != 0 {
+                    let raws = slice::from_raw_parts(stack, length as usize);
+                    raws.iter().map(|l| SourceLocation::from_raw(*l, tu)).collect()
+                } else {
+                    vec![]
+                };
+                inclusions.push((File::from_ptr(file, tu), stack));
+            }
+        }
+
+        let mut data = (self, vec![]);
+        unsafe { clang_getInclusions(self.ptr, visit, utility::addressof(&mut data)); }
+        data.1
+    }
+
+    /// Returns the files included in this translation unit along with their inclusion stacks and
+    /// whether they are system headers.
+    ///
+    /// See `get_inclusions` for a description of inclusion stacks.
+    pub fn get_inclusion_details(&'i self) -> Vec<Inclusion<'i>> {
+        self.get_inclusions().into_iter().map(|(file, stack)| {
+            Inclusion { file, stack, is_system_header: file.is_system_header() }
+        }).collect()
+    }
+
+    /// Returns the macro definitions in this translation unit, in the order they were defined.
+    ///
+    /// This will always return an empty `Vec` if this translation unit was not constructed with a
+    /// detailed preprocessing record.
+    #[cfg(feature="clang_3_9")]
+    pub fn get_macros(&'i self) -> Vec<MacroInfo<'i>> {
+        self.get_entity().get_children().into_iter().filter(|e| {
+            e.get_kind() == EntityKind::MacroDefinition
+        }).map(|e| {
+            let (parameters, body) = match e.get_macro_parts() {
+                Some((parameters, body)) => (parameters, token::join(&body)),
+                None => (None, String::new()),
+            };
+            MacroInfo {
+                name: e.get_name().unwrap_or_default(),
+                is_function_like: e.is_function_like_macro(),
+                is_builtin: e.is_builtin_macro(),
+                parameters,
+                body,
+                location: e.get_location(),
+            }
+        }).collect()
+    }
+
+    /// Returns a map from the paths of the files in this translation unit to the paths of the files
+    /// they directly include.
+    ///
+    /// Every file in this translation unit is present as a key, even if it includes no files.
+    pub fn build_include_graph(&'i self) -> HashMap<PathBuf, Vec<PathBuf>> {
+        self.get_include_edges(false)
+    }
+
+    /// Returns a map from the paths of the files in this translation unit to the paths of the files
+    /// which directly include them.
+    ///
+    /// Every file in this translation unit is present as a key, even if it is not included by any
+    /// file (e.g., the main file).
+    pub fn reverse_include_graph(&'i self) -> HashMap<PathBuf, Vec<PathBuf>> {
+        self.get_include_edges(true)
+    }
+
+    fn get_include_edges(&'i self, reverse: bool) -> HashMap<PathBuf, Vec<PathBuf>> {
+        let mut graph = HashMap::new();
+        for (file, stack) in self.get_inclusions() {
+            let included = file.get_path();
+            graph.entry(included.clone()).or_insert_with(Vec::new);
+            let includer = match stack.first().and_then(|l| l.get_file_location().file) {
+                Some(includer) => includer.get_path(),
+                None => continue,
+            };
+            let (from, to) = if reverse { (included, includer) } else { (includer, included) };
+            let edges = graph.entry(from).or_insert_with(Vec::new);
+            if !edges.contains(&to) {
+                edges.push(to);
+            }
+        }
+        graph
+    }
+
+    /// Returns the text formed by joining the spellings of the supplied tokens.
+    ///
+    /// The spacing between the tokens is reconstructed from their source locations: tokens which
+    /// are adjacent in the source file are not separated, tokens on the same line are separated by
+    /// as many spaces as separate them in the source file, and tokens on later lines are preceded
+    /// by line breaks and indented to their original columns. Tokens which are not in order or in
+    /// different files are separated by a single space. Tabs are not reproduced.
+    pub fn reconstruct_text(&'i self, tokens: &[Token<'i>]) -> String {
+        token::reconstruct(tokens)
+    }
+
+    /// Returns the innermost declaration whose extent contains the supplied location, if any.
+    ///
+    /// Unlike `SourceLocation::get_entity`, which returns the innermost AST entity at a location
+    /// (e.g., an expression), this descends from the entity for this translation unit through the
+    /// children whose extents contain the supplied location and returns the last declaration
+    /// encountered (e.g., the function that contains an expression).
+    pub fn get_enclosing_declaration_at(
+        &'i self, location: SourceLocation<'i>
+    ) -> Option<Entity<'i>> {
+        let mut declaration = None;
+        let mut entity = self.get_entity();
+        while let Some(child) = entity.get_children().into_iter().find(|c| {
+            c.get_range().is_some_and(|r| r.contains(location))
+        }) {
+            if child.is_declaration() {
+                declaration = Some(child);
+            }
+            entity = child;
+        }
+        declaration
+    }
+
+    /// Returns the AST entities in this translation unit that refer to the supplied AST entity.
+    ///
+    /// If a file is supplied, only that file is searched (see `File::get_references`). Otherwise
+    /// the whole translation unit is searched and AST entities are matched by USR, so references to
+    /// any redeclaration of the supplied AST entity are included. The declarations of the supplied
+    /// AST entity are only included if `declarations` is `true`.
+    pub fn find_references(
+        &'i self, entity: &Entity<'i>, file: Option<&File<'i>>, declarations: bool
+    ) -> Vec<Entity<'i>> {
+        let target = entity.get_reference().unwrap_or(*entity);
+        let include = |e: &Entity<'i>| declarations || !e.is_declaration();
+
+        if let Some(file) = file {
+            return file.get_references(target).into_iter().filter(include).collect();
+        }
+
+        let usr = target.get_usr();
+        let canonical = target.get_canonical_entity();
+        let mut references = vec![];
+        self.get_entity().visit_children(|e, _| {
+            let candidate = e.is_reference() || e.is_declaration() || matches!(
+                e.get_kind(),
+                EntityKind::DeclRefExpr | EntityKind::MemberRefExpr | EntityKind::ObjCMessageExpr
+            );
+            let matches = candidate && include(&e) && e.get_reference().is_some_and(|r| {
+                match usr {
+                    Some(ref usr) => r.get_usr().as_ref() == Some(usr),
+                    None => r.get_canonical_entity() == canonical,
+                }
+            });
+            if matches {
+                references.push(e);
+            }
+            EntityVisitResult::Recurse
+        });
+        references
+    }
+
+    /// Returns the location of the bracket that matches the bracket at the supplied location, if
+    /// any.
+    ///
+    /// Parentheses, square brackets, and curly braces are supported. Only the tokens of the
+    /// top-level AST entity that contains the supplied location are searched.
+    pub fn matching_bracket(&'i self, location: SourceLocation<'i>) -> Option<SourceLocation<'i>> {
+        fn get_pair(spelling: &str) -> Option<(&'static str, bool)> {
+            match spelling {
+                "(" => Some((")", true)),
+                "[" => Some(("]", true)),
+                "{" => Some(("}", true)),
+                ")" => Some(("(", false)),
+                "]" => Some(("[", false)),
+                "}" => Some(("{", false)),
+                _ => None,
+            }
+        }
+
+        let entity = self.get_entity().get_children().into_iter().find(|e| {
+            matches!(e.get_range(), Some(r) if r.contains(location))
+        })?;
+
+        let mut tokens = entity.get_range()?.tokenize();
+        let offset = location.get_spelling_location().offset;
+        let index = tokens.iter().position(|t| {
+            t.get_kind() == TokenKind::Punctuation &&
+            t.get_location().get_spelling_location().offset == offset
+        })?;
+
+        let opening = get_pair(&tokens[index].get_spelling())?.1;
+        let index = if opening {
+            index
+        } else {
+            tokens.reverse();
+            tokens.len() - index - 1
+        };
+
+        let mut stack = vec![];
+        for token in &tokens[index..] {
+            let spelling = token.get_spelling();
+            match get_pair(&spelling) {
+                Some((pair, o)) if o == opening => stack.push(pair),
+                Some(_) => {
+                    if stack.pop()? != spelling {
+                        return None;
+                    }
+                    if stack.is_empty() {
+                        return Some(token.get_location());
+                    }
+                },
+                None => { },
+            }
+        }
+
+        None
+    }
+
+    /// Returns the unsaved files in the supplied unsaved files that do not correspond to any file
+    /// in this translation unit.
+    ///
+    /// An unsaved file is ignored by `libclang` while parsing if its path does not match the path
+    /// of the main file or of a file included by the main file (e.g., a relative path was used for
+    /// the unsaved file while an absolute path was used for the main file). `Parser::parse`,
+    /// `TranslationUnit::reparse`, and `Completer::complete` report such unsaved files to the hook
+    /// set by `Clang::set_unused_unsaved_hook`, and this can be used to check for them directly.
+    pub fn get_unused_unsaved<'u>(&self, unsaved: &'u [Unsaved]) -> Vec<&'u Unsaved> {
+        unsaved.iter().filter(|u| {
+            unsafe { clang_getFile(self.ptr, u.path.as_ptr()).is_null() }
+        }).collect()
+    }
+
+    /// Calls the unused unsaved file hook (see `Clang::set_unused_unsaved_hook`) with the path of
+    /// each of the supplied unsaved files that do not correspond to any file in this translation
+    /// unit.
+    pub(crate) fn report_unused_unsaved(&self, unsaved: &[Unsaved]) {
+        let hook = match UNUSED_UNSAVED_HOOK.with(|h| h.get()) {
+            Some(hook) => hook,
+            None => return,
+        };
+        for unsaved in self.get_unused_unsaved(unsaved) {
+            hook(Path::new(&*unsaved.path.to_string_lossy()));
+        }
+    }
+
     /// Returns the memory usage of this translation unit.
+    ///
+    /// The amounts are in bytes. The resource usage returned by `libclang` is always disposed, so
+    /// this can be polled periodically (e.g., to detect growth in a long-running process).
     pub fn get_memory_usage(&self) -> HashMap<MemoryUsage, usize> {
         unsafe {
             let raw = clang_getCXTUResourceUsage(self.ptr);
@@ -3090,6 +4753,14 @@ impl<'i> TranslationUnit<'i> {
         }
     }
 
+    /// Returns the name of the main source file of this translation unit.
+    ///
+    /// This is the path of the source file as it was supplied when this translation unit was
+    /// parsed (or the path of the AST file it was loaded from).
+    pub fn get_spelling(&self) -> String {
+        unsafe { utility::to_string(clang_getTranslationUnitSpelling(self.ptr)) }
+    }
+
     /// Returns information about the target for this translation unit.
     #[cfg(feature="clang_5_0")]
     pub fn get_target(&self) -> Target {
@@ -3106,6 +4777,12 @@ impl<'i> TranslationUnit<'i> {
         }
     }
 
+    /// Returns a rewriter which edits the source files of this translation unit.
+    #[cfg(feature="clang_12_0")]
+    pub fn rewriter(&'i self) -> Rewriter<'i> {
+        Rewriter::new(self)
+    }
+
     /// Returns a completer which runs code completion.
     pub fn completer<F: Into<PathBuf>>(&self, file: F, line: u32, column: u32) -> Completer {
         Completer::new(self, file, line, column)
@@ -3124,27 +4801,84 @@ impl<'i> TranslationUnit<'i> {
         SaveError::from_error(code)
     }
 
+    /// Returns a pretty-printed JSON document that describes the AST of this translation unit.
+    ///
+    /// If a file is supplied, only the AST entities located in that file (and the root translation
+    /// unit entity) are included. Each AST entity is described by an object with the following
+    /// fields, in this order:
+    ///
+    /// * `"kind"` – the `EntityKind` of the AST entity as a string (e.g., `"FunctionDecl"`)
+    /// * `"name"` – the name of the AST entity as a string, or `null`
+    /// * `"type"` – the display name of the type of the AST entity as a string, or `null`
+    /// * `"location"` – the file location of the AST entity as an object with `"file"` (a path
+    ///   string or `null`), `"line"`, and `"column"` fields, or `null`
+    /// * `"children"` – an array of the objects that describe the children of the AST entity
+    pub fn to_json(&'i self, file: Option<&File<'i>>) -> String {
+        let mut json = String::new();
+        write_json(&self.get_entity(), file, 0, &mut json);
+        json.push('\n');
+        json
+    }
+
+    /// Returns a JSON document that describes the diagnostics for this translation unit.
+    ///
+    /// The document is an array that contains an object for each diagnostic, loosely following
+    /// the layout of the JSON diagnostics emitted by GCC (`-fdiagnostics-format=json`). Each
+    /// diagnostic is described by an object with the following fields, in this order:
+    ///
+    /// * `"severity"` – `"ignored"`, `"note"`, `"warning"`, `"error"`, or `"fatal error"`
+    /// * `"message"` – the text of the diagnostic as a string
+    /// * `"option"` – the command-line option that enables the diagnostic as a string, or `null`
+    /// * `"category"` – the name of the category of the diagnostic as a string, or `null`
+    /// * `"location"` – the spelling location of the diagnostic as an object with `"file"` (a
+    ///   path string or `null`), `"line"`, and `"column"` fields
+    /// * `"ranges"` – an array of the source ranges of the diagnostic as objects with `"start"`
+    ///   and `"finish"` location fields
+    /// * `"fixits"` – an array of the fix-its for the diagnostic as objects with `"start"` and
+    ///   `"finish"` location fields and a `"string"` field that contains the replacement text
+    /// * `"children"` – an array of the objects that describe the child diagnostics (e.g., notes)
+    pub fn diagnostics_to_json(&'i self) -> String {
+        let mut json = String::from("[");
+        for (index, diagnostic) in self.get_diagnostics().iter().enumerate() {
+            if index != 0 {
+                json.push_str(", ");
+            }
+            diagnostic::write_json(&diagnostic.to_struct(), &mut json);
+        }
+        json.push_str("]\n");
+        json
+    }
+
     //- Consumers --------------------------------
 
     /// Consumes this translation unit and reparses the source file it was created from with the
     /// same compiler arguments that were used originally.
     ///
+    /// The translation unit is reparsed with the default reparse options for this translation unit
+    /// as determined by `libclang`, so options that were set when it was parsed (e.g.,
+    /// `Parser::cache_completion_results`) continue to apply.
+    ///
+    /// Unsaved files that are ignored are reported to the hook set by
+    /// `Clang::set_unused_unsaved_hook` (see `TranslationUnit::get_unused_unsaved`).
+    ///
     /// # Failures
     ///
     /// * an error occurs while deserializing an AST file
     /// * `libclang` crashes
     /// * an unknown error occurs
     pub fn reparse(self, unsaved: &[Unsaved]) -> Result<TranslationUnit<'i>, SourceError> {
-        let unsaved = unsaved.iter().map(|u| u.as_raw()).collect::<Vec<_>>();
+        let raws = unsaved.iter().map(|u| u.as_raw()).collect::<Vec<_>>();
         unsafe {
             let code = clang_reparseTranslationUnit(
                 self.ptr,
-                unsaved.len() as c_uint,
-                unsaved.as_ptr() as *mut CXUnsavedFile,
-                CXReparse_None,
+                raws.len() as c_uint,
+                raws.as_ptr() as *mut CXUnsavedFile,
+                clang_defaultReparseOptions(self.ptr),
             );
-            SourceError::from_error(code).map(|_| self)
+            SourceError::from_error(code)?;
         }
+        self.report_unused_unsaved(unsaved);
+        Ok(self)
     }
 }
 
@@ -3156,9 +4890,8 @@ impl<'i> Drop for TranslationUnit<'i> {
 
 impl<'i> fmt::Debug for TranslationUnit<'i> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        let spelling = unsafe { clang_getTranslationUnitSpelling(self.ptr) };
         formatter.debug_struct("TranslationUnit")
-            .field("spelling", unsafe { &utility::to_string(spelling) })
+            .field("spelling", &self.get_spelling())
             .finish()
     }
 }
@@ -3215,6 +4948,43 @@ impl<'tu> Type<'tu> {
         OffsetofError::from_error(offsetof_).map(|_| offsetof_ as usize)
     }
 
+    /// Returns the offset of the nested field with the supplied path in this record type in bits
+    /// (e.g., `&["a", "b"]` for `a.b`).
+    ///
+    /// The first name in the path refers to a field in this record type and each subsequent name
+    /// refers to a field in the record type of the previous field. The offsets of the fields are
+    /// summed, so the result is relative to the start of this record type. Fields of anonymous
+    /// records are found as if they were fields of the enclosing record type.
+    ///
+    /// # Failures
+    ///
+    /// * this type or the type of a field in the path is a dependent type
+    /// * this type or the type of a field in the path is an incomplete type
+    /// * this type or the type of a field in the path is not a record type (`Parent`)
+    /// * a record type does not contain a field with the supplied name
+    #[cfg(feature="clang_3_7")]
+    pub fn get_offset_of_path(&self, path: &[&str]) -> Result<usize, OffsetofError> {
+        fn find_field<'tu>(record: Entity<'tu>, name: &str) -> Option<Entity<'tu>> {
+            record.get_children().into_iter().find_map(|c| match c.get_kind() {
+                EntityKind::FieldDecl if c.get_name().as_deref() == Some(name) => Some(c),
+                EntityKind::StructDecl | EntityKind::UnionDecl if c.is_anonymous() => {
+                    find_field(c, name)
+                },
+                _ => None,
+            })
+        }
+
+        let mut type_ = *self;
+        let mut offset = 0;
+        for name in path {
+            let record = type_.get_canonical_type();
+            offset += record.get_offsetof(name)?;
+            let field = record.get_declaration().and_then(|d| find_field(d, name));
+            type_ = field.and_then(|f| f.get_type()).ok_or(OffsetofError::Name)?;
+        }
+        Ok(offset)
+    }
+
     /// Returns the size of this type in bytes.
     ///
     /// # Failures
@@ -3274,6 +5044,27 @@ impl<'tu> Type<'tu> {
         unsafe { clang_Type_getNamedType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
     }
 
+    /// Returns the keyword written in this elaborated type, if applicable.
+    ///
+    /// Elaborated types written without a keyword (e.g., `N::A`) return `None`.
+    pub fn get_elaborated_keyword(&self) -> Option<ElaboratedKeyword> {
+        if self.get_kind() != TypeKind::Elaborated {
+            return None;
+        }
+
+        let display = self.get_display_name();
+        let display = display.trim_start_matches("const ").trim_start_matches("volatile ");
+        match display.split(' ').next() {
+            Some("class") => Some(ElaboratedKeyword::Class),
+            Some("enum") => Some(ElaboratedKeyword::Enum),
+            Some("__interface") => Some(ElaboratedKeyword::Interface),
+            Some("struct") => Some(ElaboratedKeyword::Struct),
+            Some("typename") => Some(ElaboratedKeyword::Typename),
+            Some("union") => Some(ElaboratedKeyword::Union),
+            _ => None,
+        }
+    }
+
     /// Returns the element type for this array, complex, or vector type, if applicable.
     pub fn get_element_type(&self) -> Option<Type<'tu>> {
         unsafe { clang_getElementType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
@@ -3305,13 +5096,42 @@ impl<'tu> Type<'tu> {
         }
     }
 
+    /// Returns the layout of this record type, if applicable.
+    ///
+    /// Anonymous fields (e.g., anonymous unions) are included without a name and their offsets are
+    /// relative to the start of this record type. Returns `None` if this type is not a record type
+    /// or if it is dependent or incomplete.
+    #[cfg(feature="clang_3_7")]
+    pub fn get_layout(&self) -> Option<RecordLayout<'tu>> {
+        let type_ = self.get_canonical_type();
+        let size = type_.get_sizeof().ok()?;
+        let alignment = type_.get_alignof().ok()?;
+        let fields = type_.get_fields()?.into_iter().map(|f| {
+            Some(FieldLayout {
+                name: f.get_name(),
+                offset: f.get_offset_of_field().ok()?,
+                type_: f.get_type()?,
+                bit_field_width: f.get_bit_field_width(),
+            })
+        }).collect::<Option<Vec<_>>>()?;
+        Some(RecordLayout { size, alignment, fields })
+    }
+
     /// Return the type that was modified by this attributed type.
+    ///
+    /// Attributed types are only produced for translation units parsed with
+    /// `Parser::include_attributed_types` and only for type attributes (e.g., `_Nonnull`), not for
+    /// declaration attributes such as `aligned`. `libclang` does not expose the kind of the
+    /// attribute, but the nullability attributes are available through `get_nullability`.
     #[cfg(feature="clang_8_0")]
     pub fn get_modified_type(&self) -> Option<Type<'tu>> {
         unsafe { clang_Type_getModifiedType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
     }
 
     /// Returns the nullability of this pointer type, if applicable.
+    ///
+    /// Nullability annotations are only preserved in types if the translation unit was parsed with
+    /// attributed types included (see `Parser::include_attributed_types`).
     #[cfg(feature="clang_8_0")]
     pub fn get_nullability(&self) -> Option<Nullability> {
         unsafe {
@@ -3367,8 +5187,14 @@ impl<'tu> Type<'tu> {
         }
     }
 
-    /// Returns the result type for this function or method type, if applicable.
+    /// Returns the result type for this function, method, or block pointer type, if applicable.
+    ///
+    /// For block pointer types (e.g., `int (^)(void)`), this is the result type of the pointee
+    /// block type.
     pub fn get_result_type(&self) -> Option<Type<'tu>> {
+        if self.is_block_pointer() {
+            return self.get_pointee_type()?.get_result_type();
+        }
         unsafe { clang_getResultType(self.raw).map(|t| Type::from_raw(t, self.tu)) }
     }
 
@@ -3384,11 +5210,21 @@ impl<'tu> Type<'tu> {
 
     /// Returns the template argument types for this template class specialization type, if
     /// applicable.
+    ///
+    /// If `libclang` reports no template arguments for this type itself (e.g., this type is a
+    /// typedef or an alias template specialization), the template arguments of the canonical type
+    /// are returned instead. For `template <class T> using V = std::vector<T>`, this means the type
+    /// `V<int>` reports the arguments of `std::vector<int, std::allocator<int>>`, not those of the
+    /// alias. Use `get_written_template_argument_types` to disable this fallback.
     pub fn get_template_argument_types(&self) -> Option<Vec<Option<Type<'tu>>>> {
-        iter_option!(
-            clang_Type_getNumTemplateArguments(self.raw),
-            clang_Type_getTemplateArgumentAsType(self.raw),
-        ).map(|i| i.map(|t| t.map(|t| Type::from_raw(t, self.tu))).collect())
+        self.get_written_template_argument_types().or_else(|| {
+            let canonical = self.get_canonical_type();
+            if canonical != *self {
+                canonical.get_written_template_argument_types()
+            } else {
+                None
+            }
+        })
     }
 
     /// Returns the typedef name of this type, if applicable.
@@ -3397,6 +5233,24 @@ impl<'tu> Type<'tu> {
         unsafe { utility::to_string_option(clang_getTypedefName(self.raw)) }
     }
 
+    /// Returns the template argument types for this template class specialization type as
+    /// reported by `libclang` for this exact type, if applicable.
+    ///
+    /// Unlike `get_template_argument_types`, this type is not canonicalized. Depending on the
+    /// version of `libclang`, an alias template specialization may report the arguments of the
+    /// alias or no arguments at all.
+    pub fn get_written_template_argument_types(&self) -> Option<Vec<Option<Type<'tu>>>> {
+        iter_option!(
+            clang_Type_getNumTemplateArguments(self.raw),
+            clang_Type_getTemplateArgumentAsType(self.raw),
+        ).map(|i| i.map(|t| t.map(|t| Type::from_raw(t, self.tu))).collect())
+    }
+
+    /// Returns whether this type is a block pointer type (e.g., `int (^)(void)`).
+    pub fn is_block_pointer(&self) -> bool {
+        self.get_kind() == TypeKind::BlockPointer
+    }
+
     /// Returns whether this type is qualified with const.
     pub fn is_const_qualified(&self) -> bool {
         unsafe { clang_isConstQualifiedType(self.raw) != 0 }
@@ -3442,6 +5296,9 @@ impl<'tu> Type<'tu> {
     /// Visits the fields in this record type, returning `None` if this type is not a record type
     /// and returning `Some(b)` otherwise where `b` indicates whether visitation was ended by the
     /// callback returning `false`.
+    ///
+    /// Anonymous struct and union members are visited as unnamed fields whose types are the
+    /// anonymous records, so their fields can be visited by calling this method on those types.
     #[cfg(feature="clang_3_7")]
     pub fn visit_fields<F: FnMut(Entity<'tu>) -> bool>(&self, f: F) -> Option<bool> {
         if self.get_kind() != TypeKind::Record {
@@ -3478,6 +5335,117 @@ impl<'tu> Type<'tu> {
         }
     }
 
+    /// Returns the builtin C or C++ type this type is, if applicable.
+    ///
+    /// Typedefs and other sugar are not resolved, so this returns `None` for a typedef of a
+    /// builtin type (use `get_canonical_type` first if that is desired).
+    pub fn builtin_primitive(&self) -> Option<Primitive> {
+        let primitive = match self.get_kind() {
+            TypeKind::Bool => Primitive::Bool,
+            TypeKind::CharS | TypeKind::CharU => Primitive::Char,
+            TypeKind::Char16 => Primitive::Char16,
+            TypeKind::Char32 => Primitive::Char32,
+            TypeKind::Double => Primitive::Double,
+            TypeKind::Float => Primitive::Float,
+            TypeKind::Float128 => Primitive::Float128,
+            TypeKind::Float16 => Primitive::Float16,
+            TypeKind::Half => Primitive::Half,
+            TypeKind::Int => Primitive::Int,
+            TypeKind::Int128 => Primitive::Int128,
+            TypeKind::Long => Primitive::Long,
+            TypeKind::LongDouble => Primitive::LongDouble,
+            TypeKind::LongLong => Primitive::LongLong,
+            TypeKind::SChar => Primitive::SChar,
+            TypeKind::Short => Primitive::Short,
+            TypeKind::UChar => Primitive::UChar,
+            TypeKind::UInt => Primitive::UInt,
+            TypeKind::UInt128 => Primitive::UInt128,
+            TypeKind::ULong => Primitive::ULong,
+            TypeKind::ULongLong => Primitive::ULongLong,
+            TypeKind::UShort => Primitive::UShort,
+            TypeKind::Void => Primitive::Void,
+            TypeKind::WChar => Primitive::WChar,
+            _ => return None,
+        };
+        Some(primitive)
+    }
+
+    /// Returns a suggested name for a Rust type with the same layout as this type.
+    ///
+    /// This is a best-effort mapping. Builtin types are mapped to the types in `std::os::raw`
+    /// (e.g., `c_int`), pointers and references are mapped to raw pointers, constant arrays are
+    /// mapped to arrays, function pointers are mapped to optional `extern "C"` function pointers,
+    /// and enums, records, and typedefs are mapped to their names. Any other type is mapped to its
+    /// display name.
+    pub fn to_rust_type_hint(&self) -> String {
+        let builtin = match self.get_kind() {
+            TypeKind::Void => "c_void",
+            TypeKind::Bool => "bool",
+            TypeKind::CharS | TypeKind::CharU => "c_char",
+            TypeKind::SChar => "c_schar",
+            TypeKind::UChar => "c_uchar",
+            TypeKind::Char16 => "u16",
+            TypeKind::Char32 => "u32",
+            TypeKind::Short => "c_short",
+            TypeKind::UShort => "c_ushort",
+            TypeKind::Int => "c_int",
+            TypeKind::UInt => "c_uint",
+            TypeKind::Long => "c_long",
+            TypeKind::ULong => "c_ulong",
+            TypeKind::LongLong => "c_longlong",
+            TypeKind::ULongLong => "c_ulonglong",
+            TypeKind::Int128 => "i128",
+            TypeKind::UInt128 => "u128",
+            TypeKind::Float => "c_float",
+            TypeKind::Double => "c_double",
+            _ => "",
+        };
+
+        if !builtin.is_empty() {
+            return builtin.into();
+        }
+
+        match self.get_kind() {
+            TypeKind::Pointer | TypeKind::LValueReference | TypeKind::RValueReference => {
+                let pointee = self.get_pointee_type().unwrap();
+                let canonical = pointee.get_canonical_type();
+                if canonical.get_kind() == TypeKind::FunctionPrototype {
+                    return format!("Option<{}>", canonical.to_rust_type_hint());
+                }
+
+                let mutability = if pointee.is_const_qualified() { "const" } else { "mut" };
+                format!("*{} {}", mutability, pointee.to_rust_type_hint())
+            },
+            TypeKind::ConstantArray => {
+                let element = self.get_element_type().unwrap().to_rust_type_hint();
+                format!("[{}; {}]", element, self.get_size().unwrap())
+            },
+            TypeKind::FunctionPrototype => {
+                let mut arguments = self.get_argument_types().unwrap().iter().map(|t| {
+                    t.to_rust_type_hint()
+                }).collect::<Vec<_>>();
+                if self.is_variadic() {
+                    arguments.push("...".into());
+                }
+
+                let result = self.get_result_type().unwrap();
+                let result = if result.get_kind() == TypeKind::Void {
+                    String::new()
+                } else {
+                    format!(" -> {}", result.to_rust_type_hint())
+                };
+
+                format!("unsafe extern \"C\" fn({}){}", arguments.join(", "), result)
+            },
+            TypeKind::Enum | TypeKind::Record | TypeKind::Typedef | TypeKind::Elaborated => {
+                self.get_declaration().and_then(|d| d.get_name()).unwrap_or_else(|| {
+                    self.get_display_name()
+                })
+            },
+            _ => self.get_display_name(),
+        }
+    }
+
     //- Categorization ---------------------------
 
     /// Returns whether this type is an integer type.
@@ -3548,6 +5516,7 @@ impl Unsaved {
 /// A USR identifies an AST entity and can be used to compare AST entities from different
 /// translation units.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature="serde", derive(Serialize, Deserialize))]
 pub struct Usr(pub String);
 
 impl Usr {
@@ -3598,6 +5567,28 @@ impl Usr {
         let string = utility::from_string(protocol);
         unsafe { Usr(utility::to_string(clang_constructUSR_ObjCProtocol(string.as_ptr()))) }
     }
+
+    //- Accessors --------------------------------
+
+    /// Returns this USR with the character offset removed from its source location, if any.
+    ///
+    /// The USRs of some entities that are not visible outside of a source file (e.g., local
+    /// variables and macros) begin with the name of the source file followed by the character
+    /// offset of the entity in that source file (e.g., `c:test.cpp@30@F@f#@a`). This character
+    /// offset changes whenever the preceding source code changes, so this method removes it
+    /// (e.g., `c:test.cpp@F@f#@a`). Nothing else is removed. Note that different entities may
+    /// have the same normalized USR.
+    pub fn normalize(&self) -> Usr {
+        let mut parts = self.0.split('@').collect::<Vec<_>>();
+        let file = parts[0].starts_with("c:") && parts[0].len() > 2;
+        let offset = parts.len() > 2 && !parts[1].is_empty() && parts[1].bytes().all(|b| {
+            b.is_ascii_digit()
+        });
+        if file && offset {
+            parts.remove(1);
+        }
+        Usr(parts.join("@"))
+    }
 }
 
 // Version _______________________________________
@@ -3629,7 +5620,98 @@ impl Version {
 // Functions
 //================================================
 
+/// Returns the definitions of the entity with the supplied USR in the supplied translation units.
+///
+/// Each returned AST entity borrows from the translation unit it was found in, so the definitions
+/// can be mapped back to their translation units with `Entity::get_translation_unit`. An entity
+/// defined in a header may be returned once for each translation unit that includes the header.
+pub fn find_definitions<'tu>(tus: &[&'tu TranslationUnit<'tu>], usr: &Usr) -> Vec<Entity<'tu>> {
+    tus.iter().flat_map(|tu| {
+        tu.get_entities(|e| e.is_definition() && e.get_usr().as_ref() == Some(usr))
+    }).collect()
+}
+
 /// Returns the version string for the version of `libclang` in use.
 pub fn get_version() -> String {
     unsafe { utility::to_string(clang_getClangVersion()) }
 }
+
+/// Returns the offset of the supplied source location in its file if the source location is not
+/// inside a macro expansion.
+#[cfg(not(feature="clang_17_0"))]
+fn get_file_offset(location: SourceLocation) -> Option<u32> {
+    let expansion = location.get_expansion_location();
+    if expansion.file?.get_offset_location(expansion.offset) == location {
+        Some(expansion.offset)
+    } else {
+        None
+    }
+}
+
+fn is_comment_api_loaded() -> bool {
+    clang_Cursor_getRawCommentText::is_loaded() &&
+    clang_Cursor_getBriefCommentText::is_loaded() &&
+    clang_Cursor_getCommentRange::is_loaded() &&
+    clang_Cursor_getParsedComment::is_loaded()
+}
+
+fn write_json(entity: &Entity, file: Option<&File>, indent: usize, json: &mut String) {
+    let padding = "  ".repeat(indent + 1);
+
+    json.push_str("{\n");
+    json.push_str(&padding);
+    json.push_str("\"kind\": ");
+    utility::push_json_string(json, &format!("{:?}", entity.get_kind()));
+
+    json.push_str(",\n");
+    json.push_str(&padding);
+    json.push_str("\"name\": ");
+    match entity.get_name() {
+        Some(name) => utility::push_json_string(json, &name),
+        None => json.push_str("null"),
+    }
+
+    json.push_str(",\n");
+    json.push_str(&padding);
+    json.push_str("\"type\": ");
+    match entity.get_type() {
+        Some(type_) => utility::push_json_string(json, &type_.get_display_name()),
+        None => json.push_str("null"),
+    }
+
+    json.push_str(",\n");
+    json.push_str(&padding);
+    json.push_str("\"location\": ");
+    match entity.get_location().map(|l| l.get_file_location()) {
+        Some(location) => {
+            json.push_str("{\"file\": ");
+            match location.file {
+                Some(file) => utility::push_json_string(json, &file.get_path().to_string_lossy()),
+                None => json.push_str("null"),
+            }
+            let (line, column) = (location.line, location.column);
+            json.push_str(&format!(", \"line\": {}, \"column\": {}}}", line, column));
+        },
+        None => json.push_str("null"),
+    }
+
+    json.push_str(",\n");
+    json.push_str(&padding);
+    json.push_str("\"children\": [");
+    let children = entity.get_children().into_iter().filter(|c| {
+        file.is_none() || c.get_location().and_then(|l| l.get_file_location().file).as_ref() == file
+    }).collect::<Vec<_>>();
+    for (i, child) in children.iter().enumerate() {
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        json.push_str(&padding);
+        json.push_str("  ");
+        write_json(child, file, indent + 2, json);
+    }
+    if !children.is_empty() {
+        json.push('\n');
+        json.push_str(&padding);
+    }
+    json.push_str("]\n");
+    json.push_str(&"  ".repeat(indent));
+    json.push('}');
+}
@@ -26,7 +26,11 @@ use clang_sys::*;
 use libc::{c_uint, time_t};
 
 use utility::{self, Nullable};
+#[cfg(feature="clang_12_0")]
+use utility::{FromError};
 use super::{Entity, TranslationUnit};
+#[cfg(feature="clang_12_0")]
+use super::{RewriteError};
 use super::token::{Token};
 
 //================================================
@@ -36,6 +40,9 @@ use super::token::{Token};
 // File __________________________________________
 
 /// A source file.
+///
+/// Source files are compared and hashed by their unique identifiers (see `get_id`), so the same
+/// file is considered equal to itself even when it is reached through different paths.
 #[derive(Copy, Clone)]
 pub struct File<'tu> {
     ptr: CXFile,
@@ -89,6 +96,20 @@ impl<'tu> File<'tu> {
         }
     }
 
+    /// Returns the raw contents of this file, if this file has been loaded.
+    #[cfg(feature="clang_6_0")]
+    fn get_bytes(&self) -> Option<&'tu [u8]> {
+        unsafe {
+            let mut size = 0;
+            let c = clang_getFileContents(self.tu.ptr, self.ptr, &mut size);
+            if !c.is_null() {
+                Some(slice::from_raw_parts(c as *const u8, size))
+            } else {
+                None
+            }
+        }
+    }
+
     /// Returns the module containing this file, if any.
     pub fn get_module(&self) -> Option<Module<'tu>> {
         let module = unsafe { clang_getModuleForFile(self.tu.ptr, self.ptr) };
@@ -114,6 +135,14 @@ impl<'tu> File<'tu> {
         unsafe { clang_isFileMultipleIncludeGuarded(self.tu.ptr, self.ptr) != 0 }
     }
 
+    /// Returns whether this file is a system header.
+    ///
+    /// A file is a system header if it was found in a system include directory (e.g., one
+    /// supplied with `-isystem`) or if it contains a `#pragma GCC system_header` directive.
+    pub fn is_system_header(&self) -> bool {
+        self.get_offset_location(0).is_in_system_header()
+    }
+
     /// Returns the source location at the supplied line and column in this file.
     ///
     /// # Panics
@@ -177,6 +206,12 @@ impl<'tu> fmt::Debug for File<'tu> {
     }
 }
 
+impl<'tu> fmt::Display for File<'tu> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self.get_path().display())
+    }
+}
+
 impl<'tu> cmp::PartialEq for File<'tu> {
     fn eq(&self, other: &File<'tu>) -> bool {
         self.get_id() == other.get_id()
@@ -191,6 +226,20 @@ impl<'tu> hash::Hash for File<'tu> {
     }
 }
 
+// Inclusion _____________________________________
+
+/// A file included in a translation unit.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Inclusion<'tu> {
+    /// The included file.
+    pub file: File<'tu>,
+    /// The locations of the inclusion directives that led to the file being included, starting
+    /// with the inclusion directive in the file which directly included it.
+    pub stack: Vec<SourceLocation<'tu>>,
+    /// Whether the included file is a system header.
+    pub is_system_header: bool,
+}
+
 // Location ______________________________________
 
 /// The file, line, column, and character offset of a source location.
@@ -277,6 +326,91 @@ impl<'tu> cmp::PartialEq for Module<'tu> {
 
 impl<'tu> cmp::Eq for Module<'tu> { }
 
+// Rewriter ______________________________________
+
+/// Rewrites the source files of a translation unit.
+///
+/// Edits are buffered in the rewriter until they are written out with `overwrite_changed_files` or
+/// `write_main_file_to_stdout`; the translation unit itself is not changed.
+#[cfg(feature="clang_12_0")]
+#[derive(Debug)]
+pub struct Rewriter<'tu> {
+    ptr: CXRewriter,
+    tu: &'tu TranslationUnit<'tu>,
+}
+
+#[cfg(feature="clang_12_0")]
+impl<'tu> Rewriter<'tu> {
+    //- Constructors -----------------------------
+
+    #[doc(hidden)]
+    pub fn new(tu: &'tu TranslationUnit<'tu>) -> Rewriter<'tu> {
+        let ptr = unsafe { clang_CXRewriter_create(tu.ptr) };
+        assert!(!ptr.is_null());
+        Rewriter { ptr, tu }
+    }
+
+    //- Mutators ---------------------------------
+
+    /// Inserts the supplied text before the supplied source location.
+    pub fn insert_text_before(
+        &mut self, location: SourceLocation<'tu>, text: &str
+    ) -> &mut Rewriter<'tu> {
+        let text = utility::from_string(text);
+        unsafe { clang_CXRewriter_insertTextBefore(self.ptr, location.raw, text.as_ptr()); }
+        self
+    }
+
+    /// Replaces the text in the supplied source range with the supplied text.
+    ///
+    /// The end of the source range is exclusive (i.e., the character at the end of the source
+    /// range is not replaced).
+    pub fn replace_text(&mut self, range: SourceRange<'tu>, text: &str) -> &mut Rewriter<'tu> {
+        let text = utility::from_string(text);
+        unsafe { clang_CXRewriter_replaceText(self.ptr, range.raw, text.as_ptr()); }
+        self
+    }
+
+    /// Removes the text in the supplied source range.
+    ///
+    /// The end of the source range is exclusive (i.e., the character at the end of the source
+    /// range is not removed).
+    pub fn remove_text(&mut self, range: SourceRange<'tu>) -> &mut Rewriter<'tu> {
+        unsafe { clang_CXRewriter_removeText(self.ptr, range.raw); }
+        self
+    }
+
+    /// Overwrites the source files that have been changed by this rewriter.
+    ///
+    /// # Failures
+    ///
+    /// * one or more of the changed source files could not be overwritten
+    pub fn overwrite_changed_files(&mut self) -> Result<(), RewriteError> {
+        let code = unsafe { clang_CXRewriter_overwriteChangedFiles(self.ptr) };
+        RewriteError::from_error(code)
+    }
+
+    /// Writes the main file of the translation unit, including any changes made by this
+    /// rewriter, to standard output.
+    pub fn write_main_file_to_stdout(&self) {
+        unsafe { clang_CXRewriter_writeMainFileToStdOut(self.ptr); }
+    }
+
+    //- Accessors --------------------------------
+
+    /// Returns the translation unit this rewriter rewrites the source files of.
+    pub fn get_translation_unit(&self) -> &'tu TranslationUnit<'tu> {
+        self.tu
+    }
+}
+
+#[cfg(feature="clang_12_0")]
+impl<'tu> Drop for Rewriter<'tu> {
+    fn drop(&mut self) {
+        unsafe { clang_CXRewriter_dispose(self.ptr); }
+    }
+}
+
 // SourceLocation ________________________________
 
 macro_rules! location {
@@ -430,6 +564,72 @@ impl<'tu> SourceRange<'tu> {
         unsafe { SourceLocation::from_raw(clang_getRangeEnd(self.raw), self.tu) }
     }
 
+    /// Returns the source text covered by this source range, if the file containing this source
+    /// range has been loaded.
+    ///
+    /// Returns `None` if the start and end of this source range are not in the same file.
+    #[cfg(feature="clang_6_0")]
+    pub fn get_text(&self) -> Option<String> {
+        let start = self.get_start().get_spelling_location();
+        let end = self.get_end().get_spelling_location();
+        let file = start.file?;
+        if end.file != Some(file) || start.offset > end.offset {
+            return None;
+        }
+
+        let bytes = file.get_bytes()?;
+        let text = bytes.get(start.offset as usize..end.offset as usize)?;
+        Some(String::from_utf8_lossy(text).into_owned())
+    }
+
+    fn get_offsets(&self) -> Option<(File<'tu>, u32, u32)> {
+        let start = self.get_start().get_spelling_location();
+        let end = self.get_end().get_spelling_location();
+        match (start.file, end.file) {
+            (Some(s), Some(e)) if s == e => Some((s, start.offset, end.offset)),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this source range contains the supplied source location.
+    ///
+    /// Returns `false` if this source range is null or spans multiple files.
+    pub fn contains(&self, location: SourceLocation<'tu>) -> bool {
+        let location = location.get_spelling_location();
+        match self.get_offsets() {
+            Some((file, start, end)) => {
+                location.file == Some(file) && start <= location.offset && location.offset < end
+            },
+            None => false,
+        }
+    }
+
+    /// Returns the smallest source range that covers both this source range and the supplied
+    /// source range.
+    ///
+    /// Returns `None` if the source ranges are not in the same file.
+    pub fn merge(&self, other: &SourceRange<'tu>) -> Option<SourceRange<'tu>> {
+        let (f1, s1, e1) = self.get_offsets()?;
+        let (f2, s2, e2) = other.get_offsets()?;
+        if f1 != f2 {
+            return None;
+        }
+
+        let start = if s1 <= s2 { self.get_start() } else { other.get_start() };
+        let end = if e1 >= e2 { self.get_end() } else { other.get_end() };
+        Some(SourceRange::new(start, end))
+    }
+
+    /// Returns whether this source range shares any characters with the supplied source range.
+    ///
+    /// Returns `false` if either source range is null or spans multiple files.
+    pub fn overlaps(&self, other: &SourceRange<'tu>) -> bool {
+        match (self.get_offsets(), other.get_offsets()) {
+            (Some((f1, s1, e1)), Some((f2, s2, e2))) => f1 == f2 && s1 < e2 && s2 < e1,
+            _ => false,
+        }
+    }
+
     /// Returns whether this source range is in the main file of its translation unit.
     pub fn is_in_main_file(&self) -> bool {
         self.get_start().is_in_main_file()
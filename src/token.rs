@@ -78,6 +78,13 @@ impl<'tu> Token<'tu> {
         unsafe { utility::to_string(clang_getTokenSpelling(self.tu.ptr, self.raw)) }
     }
 
+    /// Replaces the contents of the supplied buffer with the textual representation of this token.
+    ///
+    /// This avoids allocating a new `String` for every token when the buffer is reused.
+    pub fn get_spelling_into(&self, buffer: &mut String) {
+        unsafe { utility::to_string_into(clang_getTokenSpelling(self.tu.ptr, self.raw), buffer) }
+    }
+
     /// Returns the source location of this token.
     pub fn get_location(&self) -> SourceLocation<'tu> {
         unsafe { SourceLocation::from_raw(clang_getTokenLocation(self.tu.ptr, self.raw), self.tu) }
@@ -98,3 +105,50 @@ impl<'tu> fmt::Debug for Token<'tu> {
             .finish()
     }
 }
+
+//================================================
+// Functions
+//================================================
+
+/// Joins the spellings of the supplied tokens, separating tokens that are not adjacent in the
+/// source file with a single space.
+pub(crate) fn join(tokens: &[Token]) -> String {
+    let mut string = String::new();
+    let mut previous: Option<&Token> = None;
+    for token in tokens {
+        if let Some(previous) = previous {
+            let end = previous.get_range().get_end().get_spelling_location();
+            let start = token.get_location().get_spelling_location();
+            if end.file != start.file || end.offset != start.offset {
+                string.push(' ');
+            }
+        }
+        string.push_str(&token.get_spelling());
+        previous = Some(token);
+    }
+    string
+}
+
+/// Joins the spellings of the supplied tokens, reproducing the line breaks and horizontal spacing
+/// between tokens in the same source file.
+pub(crate) fn reconstruct(tokens: &[Token]) -> String {
+    let mut string = String::new();
+    let mut previous: Option<&Token> = None;
+    for token in tokens {
+        if let Some(previous) = previous {
+            let end = previous.get_range().get_end().get_spelling_location();
+            let start = token.get_location().get_spelling_location();
+            if end.file != start.file || start.offset < end.offset {
+                string.push(' ');
+            } else if start.line > end.line {
+                string.extend((end.line..start.line).map(|_| '\n'));
+                string.extend((1..start.column).map(|_| ' '));
+            } else {
+                string.extend((end.column..start.column).map(|_| ' '));
+            }
+        }
+        string.push_str(&token.get_spelling());
+        previous = Some(token);
+    }
+    string
+}
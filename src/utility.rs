@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::{Cow};
 use std::ffi::{CStr, CString};
 use std::path::{Path, PathBuf};
 
@@ -244,6 +245,50 @@ impl Nullable for CXVersion {
     }
 }
 
+//================================================
+// Structs
+//================================================
+
+// OwnedString ___________________________________
+
+/// A `CXString` which is disposed of when dropped, even if a panic occurs while it is being used.
+pub struct OwnedString(pub CXString);
+
+impl OwnedString {
+    /// Returns the contents of this string, if it is not null.
+    pub fn as_str(&self) -> Option<Cow<'_, str>> {
+        unsafe {
+            let ptr = clang_getCString(self.0);
+            if !ptr.is_null() {
+                Some(CStr::from_ptr(ptr).to_string_lossy())
+            } else {
+                None
+            }
+        }
+    }
+}
+
+impl Drop for OwnedString {
+    fn drop(&mut self) {
+        unsafe { clang_disposeString(self.0); }
+    }
+}
+
+// OwnedStringSet ________________________________
+
+/// A `CXStringSet` which is disposed of when dropped, even if a panic occurs while it is used.
+#[cfg(feature="clang_3_8")]
+pub struct OwnedStringSet(pub *mut CXStringSet);
+
+#[cfg(feature="clang_3_8")]
+impl Drop for OwnedStringSet {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { clang_disposeStringSet(self.0); }
+        }
+    }
+}
+
 //================================================
 // Functions
 //================================================
@@ -252,6 +297,22 @@ pub fn addressof<T>(value: &mut T) -> *mut c_void {
     (value as *mut T) as *mut c_void
 }
 
+pub fn push_json_string(buffer: &mut String, string: &str) {
+    buffer.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if (c as u32) < 0x20 => buffer.push_str(&format!("\\u{:04x}", c as u32)),
+            c => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+}
+
 pub fn from_path<P: AsRef<Path>>(path: P) -> CString {
     from_string(path.as_ref().as_os_str().to_str().expect("invalid C string"))
 }
@@ -266,35 +327,42 @@ pub fn from_string<S: AsRef<str>>(string: S) -> CString {
 }
 
 pub unsafe fn to_string(clang: CXString) -> String {
-        let c = CStr::from_ptr(clang_getCString(clang));
-        let rust = c.to_string_lossy().into();
-        clang_disposeString(clang);
-        rust
+    OwnedString(clang).as_str().map_or_else(String::new, |s| s.into_owned())
+}
+
+pub unsafe fn to_string_into(clang: CXString, buffer: &mut String) {
+    buffer.clear();
+    if let Some(string) = OwnedString(clang).as_str() {
+        buffer.push_str(&string);
+    }
 }
 
 pub fn to_string_option(clang: CXString) -> Option<String> {
-    clang.map(|cxs| unsafe { to_string(cxs) }).and_then(|s| {
-        if !s.is_empty() {
-            Some(s)
-        } else {
-            None
-        }
-    })
+    let string = unsafe { to_string(clang) };
+    if !string.is_empty() {
+        Some(string)
+    } else {
+        None
+    }
 }
 
 #[cfg(feature="clang_3_8")]
 pub fn to_string_set_option(clang: *mut CXStringSet) -> Option<Vec<String>> {
+    let clang = OwnedStringSet(clang);
     unsafe {
-        if clang.is_null() || (*clang).Count == 0 {
+        if clang.0.is_null() || (*clang.0).Count == 0 {
             return None;
         }
 
-        let c = ::std::slice::from_raw_parts((*clang).Strings, (*clang).Count as usize);
-        let rust = c.iter().map(|c| {
-            CStr::from_ptr(clang_getCString(*c)).to_str().expect("invalid Rust string").into()
-        }).collect();
-        clang_disposeStringSet(clang);
-        Some(rust)
+        let c = ::std::slice::from_raw_parts((*clang.0).Strings, (*clang.0).Count as usize);
+        Some(c.iter().map(|c| {
+            let ptr = clang_getCString(*c);
+            if !ptr.is_null() {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            } else {
+                String::new()
+            }
+        }).collect())
     }
 }
 
@@ -2,6 +2,15 @@ use clang::*;
 use clang::completion::*;
 
 pub fn test(clang: &Clang) {
+    // Functions _________________________________
+
+    assert_eq!(fuzzy_score("", "foo"), Some(0));
+    assert_eq!(fuzzy_score("x", "foo"), None);
+    assert_eq!(fuzzy_score("of", "foo"), None);
+    assert!(fuzzy_score("fb", "foo_bar").unwrap() > fuzzy_score("fb", "foobar").unwrap());
+    assert!(fuzzy_score("fB", "fooBar").unwrap() > fuzzy_score("fb", "fooBar").unwrap());
+    assert!(fuzzy_score("foo", "foo").unwrap() > fuzzy_score("foo", "xfoo").unwrap());
+
     // CompletionString __________________________
 
     let source = "
@@ -18,6 +27,22 @@ pub fn test(clang: &Clang) {
         let index = Index::new(&clang, false, false);
         let tu = index.parser(f).briefs_in_completion_results(true).parse().unwrap();
 
+        let mut flags = default_completion_flags();
+        flags.briefs = true;
+        flags.macros = true;
+        let completer = Completer::with_flags(&tu, f, 8, 27, flags);
+        assert!(completer.briefs_enabled());
+        assert!(completer.macros_enabled());
+        assert_eq!(completer.code_patterns_enabled(), flags.code_patterns);
+
+        let mut completer = tu.completer(f, 8, 27);
+        completer.macros(true).code_patterns(false).briefs(true);
+        assert!(completer.macros_enabled());
+        assert!(!completer.code_patterns_enabled());
+        assert!(completer.briefs_enabled());
+        completer.macros(false);
+        assert!(!completer.macros_enabled());
+
         let results = tu.completer(f, 8, 27).briefs(true).complete();
         assert_eq!(results.get_container_kind(), Some((EntityKind::StructDecl, false)));
         assert!(results.get_diagnostics(&tu).is_empty());
@@ -46,6 +71,113 @@ pub fn test(clang: &Clang) {
         assert!(!context.objc_instance_messages);
         assert!(!context.objc_class_messages);
         assert!(!context.objc_selector_names);
+        assert_eq!(context.trigger_characters(), &['.']);
+
+        let mut context = context;
+        context.dot_members = false;
+        assert_eq!(context.trigger_characters(), &[]);
+        context.arrow_members = true;
+        context.nested_name_specifiers = true;
+        context.objc_class_messages = true;
+        assert_eq!(context.trigger_characters(), &['>', ':', '[']);
+
+        let key = |r: &CompletionResult| (r.string.get_priority(), r.typed_text());
+        let iter = results.iter_results();
+        assert_eq!(iter.len(), results.get_results().len());
+        assert_eq!(
+            iter.map(|r| key(&r)).collect::<Vec<_>>(),
+            results.get_results().iter().map(key).collect::<Vec<_>>(),
+        );
+        assert_eq!(results.iter_results().filter(|r| r.kind == EntityKind::FieldDecl).count(), 3);
+
+        let groups = results.group_by_kind();
+        assert!(groups.len() >= 2);
+        assert_eq!(groups.values().map(|g| g.len()).sum::<usize>(), results.get_results().len());
+        assert!(groups.iter().all(|(k, g)| g.iter().all(|r| r.kind == *k)));
+        let fields = results.iter_results().filter(|r| r.kind == EntityKind::FieldDecl);
+        assert_eq!(groups[&EntityKind::FieldDecl], fields.collect::<Vec<_>>());
+
+        let filtered = results.filtered("a");
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].typed_text(), Some("a".into()));
+        assert!(results.filtered("A").iter().all(|r| r.typed_text() == Some("A".into())));
+        assert_eq!(results.filtered_ci("A").len(), filtered.len() + results.filtered("A").len());
+        assert!(results.filtered("zz").is_empty());
+
+        let callable = |t: &str| results.filtered(t).iter().all(|r| r.is_callable());
+        assert!(callable("operator="));
+        assert!(!callable("a"));
+        assert!(!callable("A"));
+        assert!(results.filtered_ci("OPERATOR=").iter().all(|r| r.kind == EntityKind::Method));
+
+        let index = CompletionIndex::new(&results);
+        assert_eq!(index.len(), results.get_results().len());
+        assert_eq!(index.query("").len(), index.len());
+        let typed = |rs: &[CompletionResult]| rs.iter().map(|r| r.typed_text()).collect::<Vec<_>>();
+        assert_eq!(typed(index.query("a")), &[Some("a".into())]);
+        assert!(typed(index.query("oper")).iter().all(|t| t == &Some("operator=".into())));
+        assert!(index.query("operator==").is_empty());
+        assert!(index.query("z").is_empty());
+
+        let mut manual = results.get_results();
+        manual.sort();
+        let mut sorted = tu.completer(f, 8, 27).briefs(true).complete();
+        sorted.sort();
+        assert_eq!(
+            sorted.get_results().iter().map(key).collect::<Vec<_>>(),
+            manual.iter().map(key).collect::<Vec<_>>(),
+        );
+
+        let snapshots = results.get_results().iter().map(|r| r.snapshot()).collect::<Vec<_>>();
+        assert!(!snapshots.is_empty());
+
+        let ranked = tu.completer(f, 8, 27).complete_ranked("b", 10);
+        assert_eq!(ranked[0].chunks.last(), Some(&CompletionChunkSnapshot::TypedText("b".into())));
+        assert_eq!(tu.completer(f, 8, 27).complete_ranked("", 2).len(), 2);
+        assert!(tu.completer(f, 8, 27).complete_ranked("zz", 10).is_empty());
+        let operators = tu.completer(f, 8, 27).complete_ranked("operator=", 10);
+        assert_eq!(operators.len(), 1);
+
+        let completer = tu.completer(f, 8, 27).briefs(true).clone();
+        assert_eq!(completer.complete_snapshots(), snapshots);
+        assert_eq!(completer.complete_snapshots_on_thread(), snapshots);
+
+        let mut buffer = vec![snapshots[0].clone()];
+        completer.complete_snapshots_into(&mut buffer);
+        assert_eq!(buffer, snapshots);
+        completer.complete_snapshots_into(&mut buffer);
+        assert_eq!(buffer, snapshots);
+
+        let mut chunks = vec![];
+        for result in results.iter_results() {
+            result.string.get_chunks_into(&mut chunks);
+            assert_eq!(chunks, result.string.get_chunks());
+        }
+
+        #[cfg(feature="serde")]
+        {
+            let json = serde_json::to_string(&snapshots).unwrap();
+            let parsed: Vec<CompletionResultSnapshot> = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, snapshots);
+        }
+
+        let mut cache = CompletionCache::new(1);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get_or_complete(tu.completer(f, 8, 27).briefs(true)), &snapshots[..]);
+        assert_eq!(cache.get_or_complete(tu.completer(f, 8, 27).briefs(true)), &snapshots[..]);
+        assert_eq!(cache.complete(tu.completer(f, 8, 27).briefs(true)), &snapshots[..]);
+        assert_eq!(cache.len(), 1);
+
+        assert!(cache.contains(tu.completer(f, 8, 27).briefs(true)));
+
+        let unsaved = [Unsaved::new(f, source.replace("int c;", "int c, d;"))];
+        assert!(!cache.contains(tu.completer(f, 8, 27).briefs(true).unsaved(&unsaved)));
+        let changed = cache.get_or_complete(tu.completer(f, 8, 27).briefs(true).unsaved(&unsaved));
+        assert_ne!(changed, &snapshots[..]);
+        assert_eq!(cache.len(), 1);
+        assert!(!cache.contains(tu.completer(f, 8, 27).briefs(true)));
+        cache.clear();
+        assert!(cache.is_empty());
 
         if cfg!(feature="clang_6_0") {
             return;
@@ -107,6 +239,15 @@ pub fn test(clang: &Clang) {
             CompletionChunk::TypedText("a".into()),
         ]);
 
+        assert_eq!(results[2 + offset].typed_text(), Some("a".into()));
+        assert_eq!(results[0].string.to_plain_insert(), ("operator=()".into(), Some(10)));
+        assert_eq!(results[1 + offset].string.to_plain_insert(), ("~A()".into(), None));
+        assert_eq!(results[2 + offset].string.to_plain_insert(), ("a".into(), None));
+
+        let comment = results[2 + offset].resolve_documentation(&tu).unwrap();
+        assert!(comment.as_html().contains("An integer field."));
+        assert!(results[3 + offset].resolve_documentation(&tu).is_none());
+
         assert_result_eq!(results[3 + offset], EntityKind::FieldDecl, 35, None, "A", "b", &[
             CompletionChunk::ResultType("int".into()),
             CompletionChunk::TypedText("b".into()),
@@ -122,4 +263,69 @@ pub fn test(clang: &Clang) {
             CompletionChunk::Text("::".into()),
         ]);
     });
+
+    let source = "
+        void f(int x, int y, int z = 0, int w = 1);
+        void g(int a$b);
+        int k(int x, int y); int v;
+        void h() {  }
+    ";
+
+    super::with_temporary_file("test.cpp", source, |_, f| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(f).parse().unwrap();
+
+        let results = tu.completer(f, 5, 20).complete();
+        let results = results.get_results();
+        let find = |name: &str| {
+            results.iter().find(|r| r.typed_text() == Some(name.into())).unwrap().string
+        };
+
+        let snippet = find("f").to_snippet();
+        assert!(snippet.starts_with("f(${1:int x}, ${2:int y}${3:, ${4:int z"), "{}", snippet);
+        assert!(snippet.contains("}${5:, ${6:int w"), "{}", snippet);
+        assert!(snippet.ends_with("}})"), "{}", snippet);
+        assert!(!snippet.contains("void"));
+
+        assert_eq!(find("g").to_snippet(), "g(${1:int a\\$b})");
+
+        let k = find("k");
+        assert_eq!(k.get_label(), "k");
+        assert_eq!(k.get_detail(), "int k(int x, int y)");
+        assert_eq!(k.get_insert_text(), "k()");
+
+        let v = find("v");
+        assert_eq!(v.get_label(), "v");
+        assert_eq!(v.get_detail(), "int v");
+        assert_eq!(v.get_insert_text(), "v");
+    });
+
+    let source = "
+        struct A { int a; };
+        void f() { A *p; p. }
+    ";
+
+    #[cfg(feature="clang_7_0")]
+    fn test_get_fix_its(clang: &Clang, source: &str) {
+        super::with_temporary_file("test.cpp", source, |_, f| {
+            let index = Index::new(&clang, false, false);
+            let tu = index.parser(f).parse().unwrap();
+
+            let results = tu.completer(f, 3, 28).fixits(true).complete();
+            let result = results.iter_results().find(|r| r.typed_text() == Some("a".into()));
+            let fix_its = result.unwrap().get_fix_its(&results, &tu);
+            assert_eq!(fix_its.len(), 1);
+            assert_eq!(fix_its[0].0, "->");
+
+            let file = tu.get_file(f).unwrap();
+            assert_eq!(fix_its[0].1, range!(file, 3, 27, 3, 28));
+
+            assert!(results.get_fix_its(results.iter_results().len(), &tu).is_empty());
+        });
+    }
+
+    #[cfg(not(feature="clang_7_0"))]
+    fn test_get_fix_its(_: &Clang, _: &str) { }
+
+    test_get_fix_its(clang, source);
 }
@@ -40,6 +40,13 @@ pub fn test(clang: &Clang) {
         ], &[
         ]);
 
+        let mut formatter = diagnostics[0].formatter();
+        formatter.source_location(false);
+        let option = diagnostics[0].get_option().unwrap().0;
+        let option = format!("warning: {} [{}]", text, option);
+        assert_eq!(formatter.option(true).format(), option);
+        assert_eq!(formatter.option(false).format(), format!("warning: {}", text));
+
         let text = "missing 'typename' prior to dependent type name 'T::U'";
         assert_diagnostic_eq!(diagnostics[1], Severity::Error, text, file.get_location(3, 50), &[
             range!(file, 3, 50, 3, 54)
@@ -52,5 +59,100 @@ pub fn test(clang: &Clang) {
         ], &[
             FixIt::Replacement(range!(file, 4, 50, 4, 52), ".i = ".into())
         ]);
+
+        assert_eq!(tu.get_diagnostics_filtered(Severity::Warning).len(), 3);
+        assert_eq!(tu.get_diagnostics_filtered(Severity::Fatal).len(), 0);
+        assert!(tu.has_errors());
+
+        let errors = tu.get_diagnostics_filtered(Severity::Error);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].get_text(), diagnostics[1].get_text());
+
+        let sorted = tu.get_diagnostics_sorted();
+        let texts = sorted.iter().map(|d| d.get_text()).collect::<Vec<_>>();
+        assert_eq!(texts, &[
+            diagnostics[1].get_text(), diagnostics[0].get_text(), diagnostics[2].get_text(),
+        ]);
+
+        let (enable, disable) = diagnostics[0].get_option().unwrap();
+        assert_eq!(disable, enable.replacen("-W", "-Wno-", 1));
+        assert_eq!(diagnostics[1].get_option(), None);
+        assert!(diagnostics[1].get_category().is_some());
+
+        let info = diagnostics[1].to_struct();
+        assert_eq!(info.severity, Severity::Error);
+        assert_eq!(info.text, diagnostics[1].get_text());
+        assert_eq!(info.location.file, Some(f.to_path_buf()));
+        assert_eq!((info.location.line, info.location.column), (3, 50));
+        assert_eq!(info.ranges.len(), 1);
+        assert_eq!((info.ranges[0].end.line, info.ranges[0].end.column), (3, 54));
+        assert_eq!(info.fix_its.len(), 1);
+        assert_eq!(info.option, None);
+        assert_eq!(info.category, diagnostics[1].get_category());
+        assert!(info.children.is_empty());
+
+        assert_eq!(tu.get_main_file_diagnostics(), diagnostics);
+
+        let set = tu.get_diagnostic_set();
+        assert_eq!(set.len(), 3);
+        assert!(!set.is_empty());
+        assert_eq!(set.get(1).map(|d| d.get_text()), Some(diagnostics[1].get_text()));
+        assert_eq!(set.get(3), None);
+        assert_eq!(set.get_diagnostics().len(), 3);
+    });
+
+    super::with_file(&clang, "int a = 322;", |_, file| {
+        let source = "int a = 322;";
+
+        let insertion = FixIt::Insertion(file.get_location(1, 1), "const ".into());
+        let deletion = FixIt::Deletion(range!(file, 1, 9, 1, 10));
+        let replacement = FixIt::Replacement(range!(file, 1, 5, 1, 6), "b".into());
+        let fixits = &[deletion.clone(), insertion, replacement];
+        assert_eq!(apply_fixits(source, fixits), Ok("const int b = 22;".into()));
+        assert_eq!(apply_fixits(source, &[]), Ok(source.into()));
+
+        let overlapping = FixIt::Replacement(range!(file, 1, 9, 1, 12), "0".into());
+        let fixits = &[overlapping, deletion.clone()];
+        assert_eq!(apply_fixits(source, fixits), Err(FixItError::Overlap((8, 9), (8, 11))));
+
+        assert_eq!(apply_fixits("int", &[deletion]), Err(FixItError::Range(8, 9)));
+    });
+
+    let source = "
+        void f(int);
+        void g() { f(); }
+    ";
+
+    super::with_translation_unit(&clang, "test.cpp", source, &[], |_, f, tu| {
+        let path = f.to_string_lossy().replace('\\', "\\\\");
+        let json = tu.diagnostics_to_json();
+
+        let message = "no matching function for call to 'f'";
+        let error = format!("[{{\"severity\": \"error\", \"message\": \"{}\"", message);
+        assert!(json.starts_with(&error));
+        assert!(json.contains("\"category\": \"Semantic Issue\""));
+        let location = |l, c| {
+            format!("\"location\": {{\"file\": \"{}\", \"line\": {}, \"column\": {}}}", path, l, c)
+        };
+        assert!(json.contains(&location(3, 20)));
+        assert!(json.contains("\"children\": [{\"severity\": \"note\""));
+        assert!(json.contains(&location(2, 14)));
+        assert!(json.ends_with("\"children\": []}]}]\n"));
+    });
+
+    let files = &[
+        ("test.hpp", "int a = 3.0f;"),
+        ("test.cpp", "#include \"test.hpp\"\nint b = 4.0f;"),
+    ];
+
+    super::with_temporary_files(files, |_, fs| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(&fs[1]).arguments(&["-Wconversion"]).parse().unwrap();
+        assert_eq!(tu.get_diagnostics().len(), 2);
+        assert!(!tu.has_errors());
+
+        let diagnostics = tu.get_main_file_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].get_location().get_spelling_location().line, 2);
     });
 }
@@ -1,3 +1,4 @@
+use std::collections::{HashSet};
 use std::ffi::{OsStr};
 use std::path::{Path};
 
@@ -25,6 +26,23 @@ pub fn test(clang: &Clang) {
         super::with_file(&clang, "int a = 322;", |_, g| assert!(f.get_id() != g.get_id()));
         assert_eq!(f.get_skipped_ranges(), &[]);
         assert!(!f.is_include_guarded());
+        assert_eq!(f.to_string(), p.display().to_string());
+    });
+
+    let files = &[("test.hpp", "int a;"), ("test.cpp", "#include \"./test.hpp\"")];
+    super::with_temporary_files(files, |d, fs| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(&fs[1]).parse().unwrap();
+        let header = tu.get_file(d.join("test.hpp")).unwrap();
+        let entity = tu.get_entity().get_children()[0];
+        let location = entity.get_location().unwrap().get_file_location().file.unwrap();
+        assert_eq!(header, location);
+
+        let mut set = HashSet::new();
+        set.insert(header);
+        set.insert(location);
+        assert_eq!(set.len(), 1);
+        assert!(header != tu.get_file(&fs[1]).unwrap());
     });
 
     let source = "
@@ -114,6 +132,26 @@ pub fn test(clang: &Clang) {
         }
     });
 
+    // Rewriter __________________________________
+
+    #[cfg(feature="clang_12_0")]
+    fn test_rewriter(clang: &Clang) {
+        super::with_translation_unit(&clang, "test.cpp", "int a = 322;", &[], |_, p, tu| {
+            let f = tu.get_file(p).unwrap();
+            let mut rewriter = tu.rewriter();
+            rewriter.insert_text_before(f.get_location(1, 1), "const ");
+            rewriter.replace_text(range!(f, 1, 9, 1, 12), "644");
+            rewriter.remove_text(range!(f, 1, 12, 1, 13));
+            assert_eq!(rewriter.overwrite_changed_files(), Ok(()));
+            assert_eq!(std::fs::read_to_string(p).unwrap(), "const int a = 644");
+        });
+    }
+
+    #[cfg(not(feature="clang_12_0"))]
+    fn test_rewriter(_: &Clang) { }
+
+    test_rewriter(clang);
+
     // SourceLocation ____________________________
 
     let source = "
@@ -140,4 +178,35 @@ pub fn test(clang: &Clang) {
         assert_location_eq!(range.get_end().get_spelling_location(), Some(f), 1, 6, 5);
     });
 
+    super::with_file(&clang, "int a = 322;", |_, f| {
+        #[cfg(feature="clang_6_0")]
+        fn test_get_text(file: &File) {
+            assert_eq!(range!(file, 1, 1, 1, 13).get_text(), Some("int a = 322;".into()));
+            assert_eq!(range!(file, 1, 9, 1, 12).get_text(), Some("322".into()));
+            assert_eq!(range!(file, 1, 5, 1, 5).get_text(), Some("".into()));
+        }
+
+        #[cfg(not(feature="clang_6_0"))]
+        fn test_get_text(_: &File) { }
+
+        test_get_text(&f);
+    });
+
+    super::with_file(&clang, "int a = 322;", |_, f| {
+        let range = range!(f, 1, 5, 1, 10);
+        assert!(range.contains(f.get_location(1, 5)));
+        assert!(range.contains(f.get_location(1, 9)));
+        assert!(!range.contains(f.get_location(1, 10)));
+        assert!(!range.contains(f.get_location(1, 4)));
+
+        assert!(range.overlaps(&range!(f, 1, 9, 1, 12)));
+        assert!(range.overlaps(&range!(f, 1, 1, 1, 6)));
+        assert!(!range.overlaps(&range!(f, 1, 10, 1, 12)));
+        assert!(!range.overlaps(&range!(f, 1, 1, 1, 5)));
+
+        assert_eq!(range.merge(&range!(f, 1, 9, 1, 12)), Some(range!(f, 1, 5, 1, 12)));
+        assert_eq!(range.merge(&range!(f, 1, 1, 1, 2)), Some(range!(f, 1, 1, 1, 10)));
+        assert_eq!(range.merge(&range!(f, 1, 6, 1, 7)), Some(range));
+    });
+
 }
@@ -1,5 +1,7 @@
 extern crate clang;
 extern crate libc;
+#[cfg(feature="serde")]
+extern crate serde_json;
 
 use std::env;
 use std::fs;
@@ -114,6 +116,51 @@ mod token_test;
 #[path="sonar.rs"]
 mod sonar_test;
 
+#[cfg(all(feature="debug_leaks", target_os="linux"))]
+fn test_string_leaks(clang: &Clang) {
+    fn get_resident_bytes() -> usize {
+        let statm = fs::read_to_string("/proc/self/statm").unwrap();
+        let pages = statm.split_whitespace().nth(1).unwrap().parse::<usize>().unwrap();
+        pages * unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize
+    }
+
+    let source = "
+        /// A function.
+        int function(int argument) { return argument; }
+    ";
+
+    with_translation_unit(clang, "test.cpp", source, &[], |_, f, tu| {
+        let file = tu.get_file(f).unwrap();
+        let entity = tu.get_entity().get_children()[0];
+        let tokens = entity.get_range().unwrap().tokenize();
+
+        let run = |iterations: usize| {
+            let mut buffer = String::new();
+            for _ in 0..iterations {
+                entity.get_name();
+                entity.get_display_name();
+                entity.get_usr();
+                entity.get_comment();
+                entity.get_type().unwrap().get_display_name();
+                file.get_path();
+                for token in &tokens {
+                    token.get_spelling();
+                    token.get_spelling_into(&mut buffer);
+                }
+            }
+        };
+
+        run(1_000);
+        let before = get_resident_bytes();
+        run(50_000);
+        let after = get_resident_bytes();
+        assert!(after.saturating_sub(before) < 1024 * 1024, "{} -> {}", before, after);
+    });
+}
+
+#[cfg(not(all(feature="debug_leaks", target_os="linux")))]
+fn test_string_leaks(_: &Clang) { }
+
 #[test]
 fn test() {
     let clang = Clang::new().unwrap();
@@ -128,9 +175,13 @@ fn test() {
 
     sonar_test::test(&clang);
 
+    test_string_leaks(&clang);
+
     // SourceError _______________________________
 
     assert_eq!(format!("{}", SourceError::Unknown), "an unknown error occurred");
+    let error = ParseError::from(SourceError::Crash);
+    assert_eq!(format!("{}", error), "`libclang` crashed");
 
     // Entity ____________________________________
 
@@ -193,16 +244,34 @@ fn test() {
         assert_eq!(entity.get_platform_availability(), None);
         assert_eq!(entity.get_translation_unit().get_file(f), tu.get_file(f));
         assert_eq!(entity.get_usr(), None);
+        assert_eq!(entity.get_canonical_entity(), entity);
+        assert_eq!(entity.get_definition(), None);
+        assert_eq!(entity.get_reference(), None);
+        assert_eq!(entity.get_template(), None);
+        assert_eq!(entity.get_semantic_parent(), None);
+        assert_eq!(entity.get_lexical_parent(), None);
 
         let children = entity.get_children();
         assert_eq!(children.len(), 1);
 
+        assert_eq!(children[0].get_canonical_entity(), children[0]);
+        assert_eq!(children[0].get_semantic_parent(), Some(entity));
+        assert_eq!(children[0].get_lexical_parent(), Some(entity));
+        assert_eq!(children[0].get_template(), None);
+        assert_eq!(children[0].get_template_kind(), None);
+
         assert_eq!(children[0].get_display_name(), Some("a".into()));
         assert_eq!(children[0].get_kind(), EntityKind::VarDecl);
         assert_eq!(children[0].get_location(), Some(file.get_location(1, 5)));
         assert_eq!(children[0].get_name(), Some("a".into()));
         assert_eq!(children[0].get_name_ranges(), &[range!(file, 1, 5, 1, 6)]);
+
+        let mut buffer = String::from("stale");
+        children[0].get_name_into(&mut buffer);
+        assert_eq!(buffer, "a");
         assert_eq!(children[0].get_range(), Some(range!(file, 1, 1, 1, 12)));
+        let ranges = (range!(file, 1, 5, 1, 6), range!(file, 1, 1, 1, 12));
+        assert_eq!(children[0].get_ranges(), Some(ranges));
         assert_eq!(children[0].get_translation_unit().get_file(f), tu.get_file(f));
         assert_eq!(children[0].get_platform_availability(), Some(vec![]));
         assert_eq!(children[0].get_usr(), Some(Usr("c:@a".into())));
@@ -261,6 +330,24 @@ fn test() {
         test_evaluate(&expressions);
     });
 
+    let source = "
+        enum { A = 1 << 4 };
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_3_9")]
+        fn test_evaluate_enum_constant<'tu>(constant: Entity<'tu>) {
+            assert_eq!(constant.evaluate(), None);
+            let expression = constant.get_children()[0];
+            assert_eq!(expression.evaluate(), Some(EvaluationResult::SignedInteger(16)));
+        }
+
+        #[cfg(not(feature="clang_3_9"))]
+        fn test_evaluate_enum_constant<'tu>(_: Entity<'tu>) { }
+
+        test_evaluate_enum_constant(e.get_children()[0].get_children()[0]);
+    });
+
     let source = "
         class B { };
         class A : public B {
@@ -339,6 +426,10 @@ fn test() {
         assert_eq!(children[0].get_bit_field_width(), None);
         assert_eq!(children[0].get_name(), None);
         assert_eq!(children[0].get_display_name(), None);
+
+        let mut buffer = String::from("stale");
+        children[0].get_name_into(&mut buffer);
+        assert!(buffer.is_empty());
         assert!(!children[0].is_bit_field());
 
         if !cfg!(target_os="windows") {
@@ -365,7 +456,9 @@ fn test() {
         assert_eq!(file.get_location(3, 13).get_entity(), None);
         assert_eq!(file.get_location(4, 13).get_entity(), Some(children[1]));
 
+        assert!(tu.comments_available());
         assert_eq!(children[0].get_comment(), None);
+        assert!(children[0].get_parsed_comment().is_none());
         assert_eq!(children[0].get_comment_brief(), None);
         assert_eq!(children[0].get_comment_range(), None);
 
@@ -400,6 +493,30 @@ fn test() {
         assert_eq!(children[1].get_enum_constant_value(), Some((644, 644)));
     });
 
+    let source = "
+        int integer = 322;
+        enum A : int { A_A = -1, A_B, A_C = 4, A_D };
+        enum B : unsigned char { B_A = 255, B_B = 0, B_C };
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children();
+        assert_eq!(children.len(), 3);
+
+        assert_eq!(children[0].get_enum_constants(), &[]);
+        assert_eq!(children[1].get_enum_constants(), &[
+            ("A_A".into(), EnumValue::Signed(-1)),
+            ("A_B".into(), EnumValue::Signed(0)),
+            ("A_C".into(), EnumValue::Signed(4)),
+            ("A_D".into(), EnumValue::Signed(5)),
+        ]);
+        assert_eq!(children[2].get_enum_constants(), &[
+            ("B_A".into(), EnumValue::Unsigned(255)),
+            ("B_B".into(), EnumValue::Unsigned(0)),
+            ("B_C".into(), EnumValue::Unsigned(1)),
+        ]);
+    });
+
     let source = "
         void a();
         void b() throw();
@@ -423,6 +540,30 @@ fn test() {
         test_get_exception_specification(&children[..]);
     });
 
+    let source = "namespace N { template <typename T> int a = 322; } int b = N::a<int>;";
+    with_translation_unit(&clang, "test.cpp", source, &["-std=c++14"], |_, f, tu| {
+        let file = tu.get_file(f).unwrap();
+
+        let mut reference = None;
+        tu.get_entity().visit_children(|e, _| {
+            if e.get_kind() == EntityKind::DeclRefExpr {
+                reference = Some(e);
+            }
+            EntityVisitResult::Recurse
+        });
+
+        let reference = reference.unwrap();
+        assert_eq!(reference.get_reference_name_ranges(false, false), &[range!(file, 1, 63, 1, 64)]);
+        assert_eq!(reference.get_reference_name_ranges(true, false), &[range!(file, 1, 60, 1, 64)]);
+        assert_eq!(reference.get_reference_name_ranges(true, true), &[range!(file, 1, 60, 1, 69)]);
+
+        assert_eq!(reference.highlight_ranges(), &[
+            (range!(file, 1, 60, 1, 63), HighlightKind::Qualifier),
+            (range!(file, 1, 63, 1, 64), HighlightKind::Name),
+            (range!(file, 1, 64, 1, 69), HighlightKind::TemplateArguments),
+        ]);
+    });
+
     let files = &[
         ("test.hpp", ""),
         ("test.cpp", "#include \"test.hpp\""),
@@ -439,6 +580,72 @@ fn test() {
         assert_eq!(tu.get_file(&fs[1]).unwrap().get_includes(), &[last]);
     });
 
+    let source = "
+        #define A 322
+        #define B(x, y) ((x) + (y))
+        #define C (1 + 2)
+        #define D(x, ...) f(x, __VA_ARGS__)
+        #define E
+        int a = A;
+    ";
+
+    #[cfg(feature="clang_3_9")]
+    fn test_get_macros(tu: &TranslationUnit) {
+        let macros = tu.get_macros();
+        assert!(macros.iter().any(|m| m.is_builtin && m.name == "__clang__"));
+
+        let macros = macros.into_iter().filter(|m| {
+            m.location.is_some_and(|l| l.is_in_main_file())
+        }).collect::<Vec<_>>();
+        assert_eq!(macros.len(), 5);
+
+        assert_eq!(macros[0].name, "A");
+        assert!(!macros[0].is_function_like);
+        assert!(!macros[0].is_builtin);
+        assert_eq!(macros[0].parameters, None);
+        assert_eq!(macros[0].body, "322");
+        assert_eq!(macros[0].location.unwrap().get_file_location().line, 2);
+
+        assert_eq!(macros[3].name, "D");
+        assert!(macros[3].is_function_like);
+        assert_eq!(macros[3].parameters, Some(vec!["x".into(), "...".into()]));
+        assert_eq!(macros[3].body, "f(x, __VA_ARGS__)");
+    }
+
+    #[cfg(not(feature="clang_3_9"))]
+    fn test_get_macros(_: &TranslationUnit) { }
+
+    with_temporary_file("test.cpp", source, |_, f| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(f).detailed_preprocessing_record(true).parse().unwrap();
+
+        let macros = tu.get_entity().get_children().into_iter().filter(|e| {
+            e.get_kind() == EntityKind::MacroDefinition && e.is_in_main_file()
+        }).collect::<Vec<_>>();
+        assert_eq!(macros.len(), 5);
+
+        assert_eq!(macros[0].get_macro_body(), Some("322".into()));
+        assert_eq!(macros[0].get_macro_parameters(), None);
+
+        assert_eq!(macros[1].get_macro_body(), Some("((x) + (y))".into()));
+        assert_eq!(macros[1].get_macro_parameters(), Some(vec!["x".into(), "y".into()]));
+
+        assert_eq!(macros[2].get_macro_body(), Some("(1 + 2)".into()));
+        assert_eq!(macros[2].get_macro_parameters(), None);
+
+        assert_eq!(macros[3].get_macro_body(), Some("f(x, __VA_ARGS__)".into()));
+        assert_eq!(macros[3].get_macro_parameters(), Some(vec!["x".into(), "...".into()]));
+
+        assert_eq!(macros[4].get_macro_body(), Some("".into()));
+        assert_eq!(macros[4].get_macro_parameters(), None);
+
+        let variable = tu.get_entity().get_children().into_iter().last().unwrap();
+        assert_eq!(variable.get_macro_body(), None);
+        assert_eq!(variable.get_macro_parameters(), None);
+
+        test_get_macros(&tu);
+    });
+
     let source = "
         void a() { }
         class B { void b() { } };
@@ -491,6 +698,19 @@ fn test() {
         #[cfg(not(feature="clang_3_6"))]
         fn test_get_storage_class<'tu>(_: Entity<'tu>) { }
 
+        #[cfg(feature="clang_3_6")]
+        fn test_get_mangled_name<'tu>(entity: Entity<'tu>) {
+            assert_eq!(entity.get_mangled_name(), None);
+
+            let prefix = if cfg!(target_os="macos") { "_" } else { "" };
+            let children = entity.get_children();
+            assert_eq!(children[0].get_mangled_name(), Some(format!("{}_Z1av", prefix)));
+            assert_eq!(children[1].get_mangled_name(), Some(format!("{}_ZL1bv", prefix)));
+        }
+
+        #[cfg(not(feature="clang_3_6"))]
+        fn test_get_mangled_name<'tu>(_: Entity<'tu>) { }
+
         assert_eq!(e.get_linkage(), None);
 
         let children = e.get_children();
@@ -500,6 +720,46 @@ fn test() {
         assert_eq!(children[1].get_linkage(), Some(Linkage::Internal));
 
         test_get_storage_class(e);
+
+        if !cfg!(target_os="windows") {
+            test_get_mangled_name(e);
+        }
+    });
+
+    let source = "
+        extern \"C\" void f() { int a; }
+        void g(int i) { }
+        void g(float f) { }
+        struct A { A() { } };
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_3_8")]
+        fn test_get_mangled_names<'tu>(entity: Entity<'tu>) {
+            let prefix = if cfg!(target_os="macos") { "_" } else { "" };
+            let mangled = |n: &str| Some(format!("{}{}", prefix, n));
+
+            let children = entity.get_children();
+            let function = children[0].get_children()[0];
+            assert_eq!(function.get_mangled_name(), mangled("f"));
+            assert_eq!(children[1].get_mangled_name(), mangled("_Z1gi"));
+            assert_eq!(children[2].get_mangled_name(), mangled("_Z1gf"));
+
+            let local = function.get_children()[0].get_children()[0].get_children()[0];
+            assert_eq!(local.get_kind(), EntityKind::VarDecl);
+            assert_eq!(local.get_mangled_name(), None);
+
+            let constructor = children[3].get_children()[0];
+            let names = vec![mangled("_ZN1AC1Ev").unwrap(), mangled("_ZN1AC2Ev").unwrap()];
+            assert_eq!(constructor.get_mangled_names(), Some(names));
+        }
+
+        #[cfg(not(feature="clang_3_8"))]
+        fn test_get_mangled_names<'tu>(_: Entity<'tu>) { }
+
+        if !cfg!(target_os="windows") {
+            test_get_mangled_names(e);
+        }
     });
 
     let source = "
@@ -580,6 +840,128 @@ fn test() {
         test_get_template_arguments(&children);
     });
 
+    let source = "
+        void f(int a, int* p) { a = a + 1; a += 2; a++; --a; *p = -a; !a; }
+    ";
+
+    with_entity(&clang, source, |e| {
+        let (mut binary, mut unary) = (vec![], vec![]);
+        e.visit_children(|c, _| {
+            if let Some(kind) = c.get_binary_operator_kind() {
+                binary.push(kind);
+            }
+            if let Some(kind) = c.get_unary_operator_kind() {
+                unary.push(kind);
+            }
+            EntityVisitResult::Recurse
+        });
+
+        assert_eq!(binary, &[
+            BinaryOperatorKind::Assign,
+            BinaryOperatorKind::Add,
+            BinaryOperatorKind::AddAssign,
+            BinaryOperatorKind::Assign,
+        ]);
+        assert_eq!(unary, &[
+            UnaryOperatorKind::PostIncrement,
+            UnaryOperatorKind::PreDecrement,
+            UnaryOperatorKind::Dereference,
+            UnaryOperatorKind::Minus,
+            UnaryOperatorKind::LogicalNot,
+        ]);
+        assert_eq!(e.get_children()[0].get_binary_operator_kind(), None);
+        assert_eq!(e.get_children()[0].get_unary_operator_kind(), None);
+    });
+
+    let source = "
+        #define ID(x) x
+        #define NEG(x) -x
+        #define SUM a + b
+        void f(int a, int b) { ID(a) * b; a - ID(b); SUM; NEG(a); -ID(a); !a; }
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_17_0")]
+        fn test_macros(binary: &[BinaryOperatorKind], unary: &[UnaryOperatorKind]) {
+            assert_eq!(binary, &[
+                BinaryOperatorKind::Multiply,
+                BinaryOperatorKind::Subtract,
+                BinaryOperatorKind::Add,
+            ]);
+            assert_eq!(unary, &[
+                UnaryOperatorKind::Minus,
+                UnaryOperatorKind::Minus,
+                UnaryOperatorKind::LogicalNot,
+            ]);
+        }
+
+        #[cfg(not(feature="clang_17_0"))]
+        fn test_macros(binary: &[BinaryOperatorKind], unary: &[UnaryOperatorKind]) {
+            assert_eq!(binary, &[BinaryOperatorKind::Subtract]);
+            assert_eq!(unary, &[UnaryOperatorKind::LogicalNot]);
+        }
+
+        let (mut binary, mut unary) = (vec![], vec![]);
+        e.visit_children(|c, _| {
+            binary.extend(c.get_binary_operator_kind());
+            unary.extend(c.get_unary_operator_kind());
+            EntityVisitResult::Recurse
+        });
+        test_macros(&binary, &unary);
+    });
+
+    let source = "
+        int f(int a);
+        int g(int a) { return a; }
+        struct A { void h() { g(1); } };
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_6_0")]
+        fn test_get_body_text<'tu>(children: &[Entity<'tu>]) {
+            assert_eq!(children[0].get_body_text(), None);
+            assert_eq!(children[1].get_body_text(), Some("{ return a; }".into()));
+            assert_eq!(children[2].get_body_text(), None);
+            let method = children[2].get_children()[0];
+            assert_eq!(method.get_body_text(), Some("{ g(1); }".into()));
+        }
+
+        #[cfg(not(feature="clang_6_0"))]
+        fn test_get_body_text<'tu>(_: &[Entity<'tu>]) { }
+
+        test_get_body_text(&e.get_children());
+    });
+
+    let source = "
+        template <int N> void f() { }
+        void g() { f<3>(); }
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_3_6")]
+        fn test_get_template_arguments<'tu>(e: Entity<'tu>) {
+            let mut specialization = None;
+            e.visit_children(|c, _| {
+                if c.get_kind() == EntityKind::DeclRefExpr {
+                    specialization = c.get_reference();
+                }
+                EntityVisitResult::Recurse
+            });
+
+            let specialization = specialization.unwrap();
+            assert_eq!(specialization.get_kind(), EntityKind::FunctionDecl);
+            assert_eq!(specialization.get_template(), Some(e.get_children()[0]));
+            assert_eq!(specialization.get_template_arguments(), Some(vec![
+                TemplateArgument::Integral(3, 3),
+            ]));
+        }
+
+        #[cfg(not(feature="clang_3_6"))]
+        fn test_get_template_arguments<'tu>(_: Entity<'tu>) { }
+
+        test_get_template_arguments(e);
+    });
+
     let source = "
         int integer = 322;
         typedef int Integer;
@@ -591,6 +973,13 @@ fn test() {
 
         assert_eq!(children[0].get_typedef_underlying_type(), None);
         assert_eq!(children[1].get_typedef_underlying_type(), Some(children[0].get_type().unwrap()));
+
+        assert_eq!(children[0].get_typedef_type(), None);
+        let typedef = children[1].get_typedef_type().unwrap();
+        assert_eq!(Some(typedef), children[1].get_type());
+        assert_eq!(typedef.get_kind(), TypeKind::Typedef);
+        assert_eq!(typedef.get_display_name(), "Integer");
+        assert_eq!(typedef.get_canonical_type(), children[0].get_type().unwrap());
     });
 
     let source = r#"
@@ -721,6 +1110,38 @@ fn test() {
         test_constructors(&children);
     });
 
+    let source = "
+        struct A {
+            A(int);
+            explicit A(float);
+            operator int();
+            explicit operator bool();
+        };
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_17_0")]
+        fn test_is_explicit(children: &[Entity]) {
+            assert!(!children[0].is_explicit());
+            assert!(children[1].is_explicit());
+            assert!(!children[2].is_explicit());
+            assert!(children[3].is_explicit());
+        }
+
+        #[cfg(not(feature="clang_17_0"))]
+        fn test_is_explicit(_: &[Entity]) { }
+
+        let children = e.get_children()[0].get_children();
+        assert_eq!(children.len(), 4);
+
+        assert!(!children[0].is_conversion_operator());
+        assert!(!children[1].is_conversion_operator());
+        assert!(children[2].is_conversion_operator());
+        assert!(children[3].is_conversion_operator());
+
+        test_is_explicit(&children);
+    });
+
     let source = "
         struct A {
             void a() { }
@@ -742,6 +1163,61 @@ fn test() {
         assert!(children[2].is_dynamic_call());
     });
 
+    let source = "
+        struct Foo;
+        struct Foo { };
+        enum E : int;
+        union U;
+        int x;
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children();
+        assert_eq!(children.len(), 5);
+
+        assert!(children[0].is_forward_declaration());
+        assert!(!children[1].is_forward_declaration());
+        assert!(children[2].is_forward_declaration());
+        assert!(children[3].is_forward_declaration());
+        assert!(!children[4].is_forward_declaration());
+
+        assert_eq!(e.children_iter().collect::<Vec<_>>(), children);
+        let union = e.children_iter().find(|c| c.get_kind() == EntityKind::UnionDecl);
+        assert_eq!(union, Some(children[3]));
+        assert_eq!(children[4].children_iter().count(), 0);
+    });
+
+    let source = "
+        void f();
+        template <typename T> void g();
+        struct A { A(); ~A(); void h(); int i; };
+        union U { };
+        typedef int I;
+        using J = int;
+        enum E { };
+        namespace n { }
+        int v;
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children();
+        assert_eq!(children.len(), 9);
+
+        assert!(children[0].is_function() && children[1].is_function());
+        assert!(children[2].is_record() && children[3].is_record());
+        assert!(children[4].is_typedef() && children[5].is_typedef());
+        assert!(children[6].is_enum());
+        assert!(children[7].is_namespace());
+        assert!(children[8].is_variable());
+        assert!(children.iter().all(|c| !c.is_method() && !c.is_field()));
+
+        assert!(!children[0].is_record() && !children[2].is_function());
+
+        let members = children[2].get_children();
+        assert!(members[..3].iter().all(|m| m.is_method() && !m.is_function()));
+        assert!(members[3].is_field());
+    });
+
     let source = r#"
         void a();
         void b()
@@ -854,12 +1330,14 @@ fn test() {
         fn test_get_mangled_objc_names(entity: &Entity) {
             let names = vec!["_OBJC_CLASS_$_A".into(), "_OBJC_METACLASS_$_A".into()];
             assert_eq!(entity.get_mangled_objc_names(), Some(names));
+            assert_eq!(entity.get_children()[0].get_mangled_objc_names(), None);
         }
 
         #[cfg(all(feature="clang_6_0", not(target_os="macos")))]
         fn test_get_mangled_objc_names(entity: &Entity) {
             let names = vec!["_OBJC_CLASS_A".into(), "_OBJC_METACLASS_A".into()];
             assert_eq!(entity.get_mangled_objc_names(), Some(names));
+            assert_eq!(entity.get_children()[0].get_mangled_objc_names(), None);
         }
 
         #[cfg(not(feature="clang_6_0"))]
@@ -890,6 +1368,142 @@ fn test() {
         test_get_offset_of_field(&children[0].get_children());
     });
 
+    let source = "
+        typedef int I;
+        namespace n {
+            void f(int);
+            void f(I);
+            void f(double, ...);
+            struct A {
+                void g() const &;
+                void g() &&;
+                int h;
+            };
+        }
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children()[1].get_children();
+        assert_eq!(children[0].signature_key(), "n::f(int)");
+        assert_eq!(children[0].signature_key(), children[1].signature_key());
+        assert_eq!(children[2].signature_key(), "n::f(double, ...)");
+
+        let members = children[3].get_children();
+        assert_eq!(children[3].signature_key(), "n::A");
+        assert_eq!(members[0].signature_key(), "n::A::g() const &");
+        assert_eq!(members[1].signature_key(), "n::A::g() &&");
+        assert_eq!(members[2].signature_key(), "n::A::h");
+    });
+
+    let source = "
+        int a;
+        namespace n {
+            namespace {
+                inline namespace m {
+                    struct A { int b; };
+                }
+            }
+        }
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children();
+        assert!(children[0].get_namespace_path().is_empty());
+        assert!(children[1].get_namespace_path().is_empty());
+
+        let inline = children[1].get_children()[0].get_children()[0];
+        assert_eq!(inline.get_namespace_path(), &["n"]);
+
+        let record = inline.get_children()[0];
+        assert_eq!(record.get_namespace_path(), &["n", "m"]);
+        assert_eq!(record.get_children()[0].get_namespace_path(), &["n", "m"]);
+    });
+
+    let source = "
+        void a();
+        __attribute__((deprecated)) void b();
+        __attribute__((unavailable)) void c();
+        class D { void d(); };
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children();
+        assert_eq!(children[0].get_availability(), Availability::Available);
+        assert_eq!(children[1].get_availability(), Availability::Deprecated);
+        assert_eq!(children[2].get_availability(), Availability::Unavailable);
+        assert_eq!(children[3].get_children()[0].get_availability(), Availability::Available);
+    });
+
+    let source = "
+        struct A { };
+        void f(int x, int v[4], int y = 3, A z = A(), int w[2] = nullptr);
+    ";
+
+    with_entity(&clang, source, |e| {
+        let parameters = e.get_children()[1].get_arguments().unwrap();
+        let defaults = parameters.iter().map(|p| p.parameter_has_default()).collect::<Vec<_>>();
+        assert_eq!(defaults, &[false, false, true, true, true]);
+        assert!(!e.get_children()[1].parameter_has_default());
+    });
+
+    let source = "
+        struct A { int a; void b(); };
+        struct { int c; } c;
+        int d;
+    ";
+
+    fn get_names(completions: &MemberCompletions) -> Vec<String> {
+        completions.get_results().iter().filter_map(|r| r.string.get_typed_text()).collect()
+    }
+
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, f, tu| {
+        let children = tu.get_entity().get_children();
+        let completions = children[0].complete_members(&[]).unwrap();
+        assert!(matches!(completions, MemberCompletions::Completed(_)));
+        let names = get_names(&completions);
+        assert!(names.contains(&"a".into()));
+        assert!(names.contains(&"b".into()));
+        assert!(!names.contains(&"d".into()));
+
+        let unsaved = Unsaved::new(f, source.replace("void b();", "void b(); int e;"));
+        let completions = children[0].complete_members(&[unsaved]).unwrap();
+        assert!(get_names(&completions).contains(&"e".into()));
+
+        let completions = children[1].complete_members(&[]).unwrap();
+        assert!(matches!(completions, MemberCompletions::Declared(_)));
+        assert_eq!(get_names(&completions), &["c"]);
+        assert!(children[3].complete_members(&[]).is_none());
+
+        fs::remove_file(f).unwrap();
+        let completions = children[0].complete_members(&[]).unwrap();
+        assert!(matches!(completions, MemberCompletions::Declared(_)));
+        assert_eq!(get_names(&completions), &["a", "b"]);
+    });
+
+    let source = "
+        struct A { char a; int b; short c; };
+        struct B;
+    ";
+
+    with_entity(&clang, source, |e| {
+        let children = e.get_children();
+        let fields = &[("a", 0), ("b", 4), ("c", 8)];
+        assert_eq!(children[0].verify_layout(12, 4, fields), Ok(()));
+        assert_eq!(children[0].verify_layout(12, 4, &[]), Ok(()));
+
+        let size = LayoutMismatch::Size { expected: 8, actual: 12 };
+        assert_eq!(children[0].verify_layout(8, 4, fields), Err(size));
+        let alignment = LayoutMismatch::Alignment { expected: 8, actual: 4 };
+        assert_eq!(children[0].verify_layout(12, 8, fields), Err(alignment));
+        let offset = LayoutMismatch::Offset { field: "b".into(), expected: 8, actual: 32 };
+        assert_eq!(children[0].verify_layout(12, 4, &[("a", 0), ("b", 1)]), Err(offset));
+        let name = LayoutMismatch::Offsetof("d".into(), OffsetofError::Name);
+        assert_eq!(children[0].verify_layout(12, 4, &[("d", 0)]), Err(name));
+
+        let incomplete = LayoutMismatch::Sizeof(SizeofError::Incomplete);
+        assert_eq!(children[1].verify_layout(0, 0, &[]), Err(incomplete));
+    });
+
     let source = "
         const int x = 0;
     ";
@@ -956,9 +1570,74 @@ fn test() {
         test_pretty_printer(children[0]);
     });
 
+    with_entity(&clang, "struct A { int a; void f(); };", |e| {
+        #[cfg(feature="clang_7_0")]
+        fn test_pretty_print(entity: Entity) {
+            let children = entity.get_children();
+            assert_eq!(children[0].pretty_print_default(), "int a");
+
+            let printer = entity.get_pretty_printer();
+            printer.set_flag(PrintingPolicyFlag::UseTerseOutput, true);
+            assert_eq!(entity.pretty_print_members(&printer), "struct A {}\nint a\nvoid f()");
+        }
+
+        #[cfg(not(feature="clang_7_0"))]
+        fn test_pretty_print(_: Entity) {}
+
+        test_pretty_print(e.get_children()[0]);
+    });
+
+    with_entity(&clang, "struct S { }; S s;", |e| {
+        #[cfg(feature="clang_7_0")]
+        fn test_suppress_tag_keyword(entity: Entity) {
+            let printer = entity.get_pretty_printer();
+            let keyword = if cfg!(feature="clang_16_0") { "S s" } else { "struct S s" };
+            let flag = PrintingPolicyFlag::SuppressTagKeyword;
+            assert_eq!(printer.set_flag(flag, false).print(), keyword);
+            assert_eq!(printer.set_flag(flag, true).print(), "S s");
+        }
+
+        #[cfg(not(feature="clang_7_0"))]
+        fn test_suppress_tag_keyword(_: Entity) {}
+
+        test_suppress_tag_keyword(e.get_children()[1]);
+    });
+
+    with_translation_unit(&clang, "test.cpp", "struct A { int a; }; int b;", &[], |_, _, tu| {
+        #[cfg(feature="clang_7_0")]
+        fn test_pretty_print_all(tu: &TranslationUnit) {
+            let entity = tu.get_entity().get_children()[0];
+            let printer = entity.get_pretty_printer();
+            printer.set_flag(PrintingPolicyFlag::UseTerseOutput, true);
+            let printed = tu.pretty_print_all(&printer, |e| e.is_in_main_file());
+            let strings = printed.iter().map(|p| &p.1[..]).collect::<Vec<_>>();
+            assert_eq!(strings, &["struct A {}", "int a", "int b"]);
+            assert_eq!(printed[1].0, entity.get_children()[0]);
+        }
+
+        #[cfg(not(feature="clang_7_0"))]
+        fn test_pretty_print_all(_: &TranslationUnit) {}
+
+        test_pretty_print_all(&tu);
+    });
+
+    with_entity(&clang, "int a; int b; int c;", |e| {
+        assert_eq!(e.source_order_key(), None);
+
+        let mut children = e.get_children();
+        let keys = children.iter().map(|c| c.source_order_key().unwrap()).collect::<Vec<_>>();
+        assert_eq!(keys.iter().map(|k| k.1).collect::<Vec<_>>(), &[4, 11, 18]);
+        assert!(keys.iter().all(|k| k.0 == keys[0].0));
+
+        children.reverse();
+        children.sort_by_key(|c| c.source_order_key());
+        assert_eq!(children, e.get_children());
+    });
+
     let source = "
         @interface Foo
         - @property NSString *x;
+        @property (getter=isY, setter=assignY:) int y;
         @end
     ";
 
@@ -969,6 +1648,10 @@ fn test() {
         fn test_get_objc_getter_setter_name(properties: &[Entity]) {
             assert_eq!(properties[0].get_objc_getter_name().as_ref().map(|s| s.as_ref()), Some("x"));
             assert_eq!(properties[0].get_objc_setter_name().as_ref().map(|s| s.as_ref()), Some("setX:"));
+            assert_eq!(properties[0].get_objc_property_getter_name(), None);
+            assert_eq!(properties[0].get_objc_property_setter_name(), None);
+            assert_eq!(properties[1].get_objc_property_getter_name(), Some("isY".into()));
+            assert_eq!(properties[1].get_objc_property_setter_name(), Some("assignY:".into()));
         }
 
         #[cfg(not(feature="clang_8_0"))]
@@ -977,6 +1660,81 @@ fn test() {
         test_get_objc_getter_setter_name(&children[1].get_children());
     });
 
+    let source = "
+        @protocol P
+        - (void)a;
+        @optional
+        - (void)b;
+        @property int c;
+        @required
+        - (void)d;
+        @end
+    ";
+
+    with_translation_unit(&clang, "test.m", source, &[], |_, _, tu| {
+        let children = tu.get_entity().get_children()[0].get_children();
+        let optional = children.iter().map(|c| c.is_objc_optional()).collect::<Vec<_>>();
+        assert_eq!(optional.len(), 4);
+        assert_eq!(optional, &[false, true, true, false]);
+
+        assert_eq!(children[0].get_objc_selector_piece_count(), 1);
+        assert_eq!(children[2].get_objc_selector_piece_count(), 1);
+    });
+
+    let source = "
+        @interface A
+        - (void)setX:(int)x y:(int)y;
+        @end
+        void f(A *a) { [a setX:1 y:2]; }
+    ";
+
+    with_translation_unit(&clang, "test.m", source, &[], |_, _, tu| {
+        let children = tu.get_entity().get_children();
+        let method = children[0].get_children()[0];
+        assert_eq!(method.get_objc_selector_piece_count(), 2);
+        assert_eq!(method.get_name_ranges().len(), 2);
+
+        let message = children[1].get_children()[1].get_children()[0];
+        assert_eq!(message.get_kind(), EntityKind::ObjCMessageExpr);
+        assert_eq!(message.get_objc_selector_piece_count(), 2);
+        assert_eq!(children[1].get_objc_selector_piece_count(), 1);
+        assert_eq!(children[1].get_children()[1].get_objc_selector_piece_count(), 0);
+
+        let pieces = |e: Entity| {
+            e.get_objc_selector_pieces().into_iter().map(|(p, r)| {
+                let location = r.get_start().get_file_location();
+                (p, location.line, location.column)
+            }).collect::<Vec<_>>()
+        };
+        assert_eq!(pieces(method), &[("setX".into(), 3, 17), ("y".into(), 3, 29)]);
+        assert_eq!(pieces(message), &[("setX".into(), 5, 27), ("y".into(), 5, 34)]);
+        assert!(children[1].get_objc_selector_pieces().is_empty());
+    });
+
+    // CompilationDatabase _______________________
+
+    let database = r#"[
+        {"directory": "/a", "command": "clang -c a.c -DA", "file": "a.c"},
+        {"directory": "/b", "command": "clang -c b.c", "file": "b.c"}
+    ]"#;
+
+    with_temporary_files(&[("compile_commands.json", database)], |d, _| {
+        let database = CompilationDatabase::from_directory(d).unwrap();
+        let commands = database.get_all_compile_commands();
+        assert_eq!(commands.len(), 2);
+        assert!(!commands.is_empty());
+        assert!(commands.get(2).is_none());
+        assert_eq!(commands.get(1).unwrap().get_directory(), Path::new("/b"));
+        assert_eq!(commands.iter().len(), 2);
+        assert_eq!((&commands).into_iter().count(), 2);
+
+        let commands = commands.into_iter().collect::<Vec<_>>();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].directory, Path::new("/a"));
+        assert_eq!(commands[0].arguments, &["clang", "-c", "a.c", "-DA"]);
+        assert_eq!(commands[1].arguments, &["clang", "-c", "b.c"]);
+    });
+
     // Index _____________________________________
 
     let mut index = Index::new(&clang, false, false);
@@ -1002,19 +1760,328 @@ fn test() {
         let _ = index.parser(f).unsaved(&[Unsaved::new(f, "int a = 644;")]).parse().unwrap();
     });
 
+    with_temporary_file("test.cpp", "int a = 322;", |_, f| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(f).parse_checked().unwrap();
+        assert!(tu.get_diagnostics().is_empty());
+        assert!(!tu.has_errors());
+
+        let unsaved = &[Unsaved::new(f, "int a = b;\nint c = 3.0;")];
+        let arguments = &["-Wconversion"];
+        let result = index.parser(f).unsaved(unsaved).arguments(arguments).parse_checked();
+        match result {
+            Err(ParseError::Diagnostics(diagnostics)) => {
+                assert_eq!(diagnostics.len(), 2);
+                assert_eq!(diagnostics[0].severity, diagnostic::Severity::Error);
+                assert_eq!(diagnostics[1].severity, diagnostic::Severity::Warning);
+                let error = ParseError::Diagnostics(diagnostics.clone());
+                let text = "the translation unit contains errors: use of undeclared identifier 'b'";
+                assert_eq!(error.to_string(), text);
+            },
+            _ => unreachable!(),
+        }
+    });
+
+    let source = "static_assert(sizeof(void*) == 4, \"\");";
+    with_temporary_file("test.cpp", source, |d, f| {
+        let index = Index::new(&clang, false, false);
+        let mut parser = index.parser(f);
+        parser.target_triple("i686-unknown-linux-gnu").sysroot(d).arguments(&["-std=c++11"]);
+        assert!(parser.parse().unwrap().get_diagnostics().is_empty());
+        parser.target_triple("x86_64-unknown-linux-gnu");
+        assert_eq!(parser.parse().unwrap().get_diagnostics().len(), 1);
+    });
+
+    with_temporary_file("test.cpp", "int a = 322;", |d, f| {
+        let index = Index::new(&clang, false, false);
+        let unsaved = &[Unsaved::new(f, "int a = 644;"), Unsaved::new(d.join("test.hpp"), "")];
+        static UNUSED: AtomicUsize = AtomicUsize::new(0);
+        fn hook(path: &Path) {
+            assert_eq!(path.file_name().unwrap(), "test.hpp");
+            UNUSED.fetch_add(1, Ordering::SeqCst);
+        }
+
+        assert_eq!(clang.set_unused_unsaved_hook(Some(hook)), None);
+        let tu = index.parser(f).unsaved(unsaved).parse().unwrap();
+        assert_eq!(tu.get_unused_unsaved(unsaved), &[&unsaved[1]]);
+        assert_eq!(UNUSED.load(Ordering::SeqCst), 1);
+        let tu = tu.reparse(unsaved).unwrap();
+        assert_eq!(UNUSED.load(Ordering::SeqCst), 2);
+        let _ = tu.completer(f, 1, 5).unsaved(unsaved).complete();
+        assert_eq!(UNUSED.load(Ordering::SeqCst), 3);
+        let _ = tu.completer(f, 1, 5).unsaved(&unsaved[..1]).complete();
+        assert_eq!(UNUSED.load(Ordering::SeqCst), 3);
+        clang.set_unused_unsaved_hook(None);
+    });
+
     with_translation_unit(&clang, "test.cpp", "int a = 322;", &[], |d, _, tu| {
         assert_eq!(tu.get_file(d.join("test.c")), None);
     });
 
+    let files = &[
+        ("test.hpp", "int a = 322;"),
+        ("test.cpp", "#include \"test.hpp\"\nstruct B { int b; }; int c = 322;"),
+    ];
+
+    with_temporary_files(files, |_, fs| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(&fs[1]).parse().unwrap();
+
+        let names = |es: Vec<Entity>| es.iter().map(|e| e.get_name().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names(tu.get_entities(|_| true)), &["a", "B", "b", "c"]);
+        assert_eq!(names(tu.get_entities(|e| e.is_in_main_file())), &["B", "b", "c"]);
+        assert_eq!(names(tu.get_entities(|e| e.get_kind() == EntityKind::VarDecl)), &["a", "c"]);
+    });
+
+    let source = "
+        struct A;
+        struct A { int a; };
+        struct A;
+        class B;
+        union C { int c; };
+        struct D { struct { int d; } e; struct { int f; } g; };
+    ";
+
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, _, tu| {
+        let children = tu.get_entity().get_children();
+        let records = tu.collect_unique_records();
+        assert_eq!(records.len(), 6);
+        assert_eq!(records[0], children[1]);
+        assert!(records[0].is_definition());
+        assert_eq!(records[1], children[3]);
+        assert!(!records[1].is_definition());
+        assert_eq!(records[2], children[4]);
+        assert_eq!(records[3], children[5]);
+        assert!(records[4..].iter().all(|r| r.get_semantic_parent() == Some(children[5])));
+    });
+
+    let source = "
+        void f();
+        void f() { int a; }
+        struct S { int b; };
+    ";
+
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, f, tu| {
+        let records = tu.collect_symbol_records();
+        let names = records.iter().map(|r| r.name.clone().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, &["f", "f", "a", "S", "b"]);
+        assert_eq!(records[0].usr, Usr("c:@F@f#".into()));
+        assert_eq!(records[0].usr, records[1].usr);
+        assert_eq!(records[0].kind, EntityKind::FunctionDecl);
+        assert_eq!(records[0].file.as_deref(), Some(f));
+        assert_eq!((records[0].line, records[0].column), (2, 14));
+        assert!(!records[0].is_definition);
+        assert!(records[1].is_definition);
+        assert_eq!(records[4].kind, EntityKind::FieldDecl);
+    });
+
+    let source = "
+        /// A namespace.
+        namespace N {
+            /// A class.
+            class A {
+            public:
+                /// A method.
+                void f(int a);
+                void g();
+            private:
+                int b;
+            };
+        }
+        int c;
+    ";
+
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, _, tu| {
+        let coverage = tu.documentation_coverage().into_iter().map(|(e, d)| {
+            (e.get_name().unwrap(), d)
+        }).collect::<Vec<_>>();
+        assert_eq!(coverage, &[
+            ("N".into(), true),
+            ("A".into(), true),
+            ("f".into(), true),
+            ("g".into(), false),
+            ("c".into(), false),
+        ]);
+    });
+
+    let source = "int a[2] = { 1, 2 }; int f(int b) { return (b + (a[0])); }";
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, f, tu| {
+        let file = tu.get_file(f).unwrap();
+        let matching = |c| tu.matching_bracket(file.get_location(1, c));
+        assert_eq!(matching(6), Some(file.get_location(1, 8)));
+        assert_eq!(matching(8), Some(file.get_location(1, 6)));
+        assert_eq!(matching(12), Some(file.get_location(1, 19)));
+        assert_eq!(matching(19), Some(file.get_location(1, 12)));
+        assert_eq!(matching(35), Some(file.get_location(1, 58)));
+        assert_eq!(matching(44), Some(file.get_location(1, 55)));
+        assert_eq!(matching(55), Some(file.get_location(1, 44)));
+        assert_eq!(matching(51), Some(file.get_location(1, 53)));
+        assert_eq!(matching(1), None);
+        assert_eq!(matching(37), None);
+    });
+
+    let source = "struct A { void g() { 1 + 2; } }; int y = 3;";
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, f, tu| {
+        let file = tu.get_file(f).unwrap();
+        let enclosing = |c| tu.get_enclosing_declaration_at(file.get_location(1, c));
+        let name = |c| enclosing(c).map(|e| (e.get_kind(), e.get_name().unwrap()));
+        assert_eq!(name(8), Some((EntityKind::StructDecl, "A".into())));
+        assert_eq!(name(23), Some((EntityKind::Method, "g".into())));
+        assert_eq!(name(43), Some((EntityKind::VarDecl, "y".into())));
+        assert_eq!(name(44), None);
+    });
+
+    let source = "void f(); void g() { f(); f(); }";
+    with_translation_unit(&clang, "test.cpp", source, &[], |_, f, tu| {
+        let file = tu.get_file(f).unwrap();
+        let declaration = tu.get_entity().get_children()[0];
+        let columns = |references: Vec<Entity>| {
+            references.iter().map(|r| r.get_location().unwrap().get_file_location().column)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(columns(tu.find_references(&declaration, None, false)), &[22, 27]);
+        assert_eq!(columns(tu.find_references(&declaration, None, true)), &[6, 22, 27]);
+        assert_eq!(columns(tu.find_references(&declaration, Some(&file), false)), &[22, 27]);
+        assert_eq!(columns(tu.find_references(&declaration, Some(&file), true)), &[6, 22, 27]);
+
+        let reference = tu.find_references(&declaration, None, false)[0];
+        assert_eq!(columns(tu.find_references(&reference, None, false)), &[22, 27]);
+    });
+
     with_translation_unit(&clang, "test.cpp", "int a = 322;", &[], |_, _, tu| {
         let usage = tu.get_memory_usage();
         assert_eq!(usage.get(&MemoryUsage::Selectors), Some(&0));
+        assert!(usage[&MemoryUsage::Ast] > 0);
+        assert!(usage[&MemoryUsage::Identifiers] > 0);
+        assert!(usage.values().sum::<usize>() < 1024 * 1024 * 1024);
+    });
+
+    with_translation_unit(&clang, "test.cpp", "int a = 322;", &[], |_, f, tu| {
+        assert_eq!(Path::new(&tu.get_spelling()), f);
     });
 
     with_translation_unit(&clang, "test.cpp", "int a = 322;", &[], |_, f, tu| {
         let _ = tu.reparse(&[Unsaved::new(f, "int a = 644;")]).unwrap();
     });
 
+    with_temporary_file("test.cpp", "struct A { int a; };\nint b;", |_, f| {
+        let index = Index::new(&clang, false, false);
+        let mut parser = index.parser(f);
+        parser.cache_completion_results(true);
+        let tu = parser.parse().unwrap();
+
+        let contents = "struct A { int a; int b; };\nvoid f(A a) { a. }";
+        let unsaved = &[Unsaved::new(f, contents)];
+        let tu = tu.reparse(unsaved).unwrap();
+        let declarations = tu.get_entity().get_children();
+        assert_eq!(declarations.len(), 2);
+        assert_eq!(declarations[0].get_children().len(), 2);
+
+        let results = tu.completer(f, 2, 17).unsaved(unsaved).complete();
+        let names = results.get_results().iter().filter_map(|r| r.typed_text()).collect::<Vec<_>>();
+        assert!(names.contains(&"b".into()));
+    });
+
+    let files = &[
+        ("a.hpp", "void f(); inline void g() { }"),
+        ("a.cpp", "#include \"a.hpp\"\nvoid f() { }"),
+        ("b.cpp", "#include \"a.hpp\"\nvoid h() { f(); }"),
+    ];
+    with_temporary_files(files, |_, fs| {
+        let index = Index::new(&clang, false, false);
+        let a = index.parser(&fs[1]).parse().unwrap();
+        let b = index.parser(&fs[2]).parse().unwrap();
+
+        let definitions = find_definitions(&[&a, &b], &Usr("c:@F@f#".into()));
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].get_translation_unit().get_file(&fs[1]), a.get_file(&fs[1]));
+        assert_eq!(definitions[0].get_location().unwrap().get_file_location().line, 2);
+
+        assert_eq!(find_definitions(&[&a, &b], &Usr("c:@F@g#".into())).len(), 2);
+        assert!(find_definitions(&[&b], &Usr("c:@F@f#".into())).is_empty());
+    });
+
+    let files = &[
+        ("a.hpp", "#pragma once\nint a;"),
+        ("b.hpp", "#pragma once\n#include \"a.hpp\"\nint b;"),
+        ("test.cpp", "#include \"b.hpp\"\n#include \"a.hpp\"\nint c;"),
+    ];
+    with_temporary_files(files, |_, fs| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(&fs[2]).parse().unwrap();
+
+        let inclusions = tu.get_inclusions();
+        assert_eq!(inclusions.len(), 3);
+        let main = inclusions.iter().find(|i| i.0.get_path() == fs[2]).unwrap();
+        assert!(main.1.is_empty());
+        let a = inclusions.iter().find(|i| i.0.get_path() == fs[0]).unwrap();
+        assert_eq!(a.1.len(), 2);
+        assert_eq!(a.1[0].get_file_location().line, 2);
+        assert_eq!(a.1[1].get_file_location().line, 1);
+
+        let graph = tu.build_include_graph();
+        assert_eq!(graph.len(), 3);
+        assert_eq!(graph[&fs[2]], &[fs[1].clone()]);
+        assert_eq!(graph[&fs[1]], &[fs[0].clone()]);
+        assert!(graph[&fs[0]].is_empty());
+
+        let graph = tu.reverse_include_graph();
+        assert_eq!(graph.len(), 3);
+        assert!(graph[&fs[2]].is_empty());
+        assert_eq!(graph[&fs[1]], &[fs[2].clone()]);
+        assert_eq!(graph[&fs[0]], &[fs[1].clone()]);
+    });
+
+    let files = &[
+        ("a.hpp", "int a;"),
+        ("s.hpp", "int s;"),
+        ("test.cpp", "#include \"a.hpp\"\n#include <s.hpp>\nint c;"),
+    ];
+    with_temporary_files(files, |d, fs| {
+        let index = Index::new(&clang, false, false);
+        let arguments = &["-isystem", d.to_str().unwrap()];
+        let tu = index.parser(&fs[2]).arguments(arguments).parse().unwrap();
+
+        let inclusions = tu.get_inclusion_details();
+        assert_eq!(inclusions.len(), 3);
+        let find = |p: &PathBuf| inclusions.iter().find(|i| &i.file.get_path() == p).unwrap();
+        assert!(!find(&fs[2]).is_system_header);
+        assert!(find(&fs[2]).stack.is_empty());
+        assert!(!find(&fs[0]).is_system_header);
+        assert!(find(&fs[1]).is_system_header);
+        assert_eq!(find(&fs[1]).stack[0].get_file_location().line, 2);
+        assert!(find(&fs[1]).file.is_system_header());
+    });
+
+    let files = &[("test.hpp", "int a;"), ("test.cpp", "#include \"test.hpp\"\nint b;")];
+    with_temporary_files(files, |_, fs| {
+        let index = Index::new(&clang, false, false);
+        let tu = index.parser(&fs[1]).parse().unwrap();
+        let file = tu.get_file(&fs[1]).unwrap();
+
+        let path = fs[1].to_str().unwrap();
+        assert_eq!(tu.to_json(Some(&file)), format!(r#"{{
+  "kind": "TranslationUnit",
+  "name": {:?},
+  "type": null,
+  "location": null,
+  "children": [
+    {{
+      "kind": "VarDecl",
+      "name": "b",
+      "type": "int",
+      "location": {{"file": {:?}, "line": 2, "column": 5}},
+      "children": []
+    }}
+  ]
+}}
+"#, path, path));
+
+        let json = tu.to_json(None);
+        assert!(json.contains("\"name\": \"a\""));
+        assert!(json.contains("\"name\": \"b\""));
+    });
+
     // Type ______________________________________
 
     with_entity(&clang, "int a = 322;", |e| {
@@ -1055,6 +2122,37 @@ fn test() {
         assert_eq!(ts[1].get_sizeof(), Ok(size * 2));
     });
 
+    let source = "
+        struct A { char a; int b; };
+        struct B { double c; struct A d; union { short e; A f; }; };
+        typedef struct B C;
+        struct D;
+        struct E { struct D *g; };
+    ";
+
+    with_types(&clang, source, |ts| {
+        #[cfg(feature="clang_3_7")]
+        fn test_get_offset_of_path(types: &[Type]) {
+            let size = mem::size_of::<c_int>();
+            assert_eq!(types[1].get_offset_of_path(&["d"]), Ok(64));
+            assert_eq!(types[1].get_offset_of_path(&["d", "b"]), Ok(64 + size * 8));
+            assert_eq!(types[1].get_offset_of_path(&["f", "b"]), Ok(64 + size * 16 + size * 8));
+            assert_eq!(types[2].get_offset_of_path(&["d", "b"]), Ok(64 + size * 8));
+            assert_eq!(types[1].get_offset_of_path(&[]), Ok(0));
+
+            assert_eq!(types[1].get_offset_of_path(&["d", "z"]), Err(OffsetofError::Name));
+            assert_eq!(types[1].get_offset_of_path(&["c", "a"]), Err(OffsetofError::Parent));
+            assert_eq!(types[4].get_offset_of_path(&["g", "a"]), Err(OffsetofError::Parent));
+            let pointee = types[4].get_fields().unwrap()[0].get_type().unwrap().get_pointee_type();
+            assert_eq!(pointee.unwrap().get_offset_of_path(&["a"]), Err(OffsetofError::Incomplete));
+        }
+
+        #[cfg(not(feature="clang_3_7"))]
+        fn test_get_offset_of_path(_: &[Type]) { }
+
+        test_get_offset_of_path(&ts);
+    });
+
     let source = "
         int integer = 322;
         void a() { }
@@ -1112,6 +2210,27 @@ fn test() {
         fn test_get_elaborated_type<'tu>(_: &[Type<'tu>]) { }
 
         test_get_elaborated_type(&ts);
+
+        assert_eq!(ts[0].get_elaborated_keyword(), None);
+        assert_eq!(ts[2].get_elaborated_keyword(), Some(ElaboratedKeyword::Class));
+    });
+
+    let source = "
+        struct S { };
+        union U { };
+        enum E { };
+        namespace N { struct A { }; }
+        struct S s;
+        union U u;
+        enum E e;
+        N::A a;
+    ";
+
+    with_types(&clang, source, |ts| {
+        assert_eq!(ts[3].get_elaborated_keyword(), Some(ElaboratedKeyword::Struct));
+        assert_eq!(ts[4].get_elaborated_keyword(), Some(ElaboratedKeyword::Union));
+        assert_eq!(ts[5].get_elaborated_keyword(), Some(ElaboratedKeyword::Enum));
+        assert_eq!(ts[6].get_elaborated_keyword(), None);
     });
 
     let source = "
@@ -1163,6 +2282,165 @@ fn test() {
         test_get_fields(e.get_children()[0]);
     });
 
+    let source = "
+        struct A { char a; double b; short c; int d : 3; int e : 5; union { int f; char g; }; };
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_3_7")]
+        fn test_get_layout<'tu>(entity: Entity<'tu>) {
+            let layout = entity.get_type().unwrap().get_layout().unwrap();
+            assert_eq!((layout.size, layout.alignment), (24, 8));
+
+            let offsets = layout.fields.iter().map(|f| f.offset).collect::<Vec<_>>();
+            assert_eq!(offsets, &[0, 64, 128, 144, 147, 160]);
+            let names = layout.fields.iter().take(5).map(|f| f.name.clone().unwrap());
+            assert_eq!(names.collect::<Vec<_>>(), &["a", "b", "c", "d", "e"]);
+            let widths = layout.fields.iter().map(|f| f.bit_field_width).collect::<Vec<_>>();
+            assert_eq!(widths, &[None, None, None, Some(3), Some(5), None]);
+            assert_eq!(layout.fields[1].type_.get_kind(), TypeKind::Double);
+            assert_eq!(layout.fields[5].type_.get_sizeof(), Ok(4));
+
+            let int = entity.get_children()[0].get_type().unwrap();
+            assert_eq!(int.get_layout(), None);
+        }
+
+        #[cfg(not(feature="clang_3_7"))]
+        fn test_get_layout<'tu>(_: Entity<'tu>) { }
+
+        if cfg!(all(unix, target_pointer_width="64")) {
+            test_get_layout(e.get_children()[0]);
+        }
+    });
+
+    let source = "
+        struct A { int a; union { int b; float c; }; int d; };
+    ";
+
+    with_entity(&clang, source, |e| {
+        #[cfg(feature="clang_3_7")]
+        fn test_visit_fields<'tu>(entity: Entity<'tu>) {
+            let type_ = entity.get_type().unwrap();
+
+            let mut fields = vec![];
+            assert_eq!(type_.visit_fields(|f| { fields.push(f); true }), Some(false));
+            assert_eq!(fields.len(), 3);
+            assert_eq!(fields[0].get_name(), Some("a".into()));
+            assert_eq!(fields[2].get_name(), Some("d".into()));
+
+            let anonymous = fields[1].get_type().unwrap().get_canonical_type();
+            let union = anonymous.get_declaration().unwrap();
+            assert_eq!(union.get_kind(), EntityKind::UnionDecl);
+            assert!(union.is_anonymous());
+
+            let mut names = vec![];
+            let stopped = anonymous.visit_fields(|f| {
+                names.push(f.get_name().unwrap());
+                false
+            });
+            assert_eq!(stopped, Some(true));
+            assert_eq!(names, &["b"]);
+
+            assert_eq!(fields[0].get_type().unwrap().visit_fields(|_| true), None);
+        }
+
+        #[cfg(not(feature="clang_3_7"))]
+        fn test_visit_fields<'tu>(_: Entity<'tu>) { }
+
+        test_visit_fields(e.get_children()[0]);
+    });
+
+    let source = "
+        int a;
+        const char* b;
+        unsigned char c[4];
+        struct S { int x; } d;
+        int (*e)(int, float);
+        void* f;
+    ";
+
+    with_types(&clang, source, |ts| {
+        assert_eq!(ts[0].to_rust_type_hint(), "c_int");
+        assert_eq!(ts[1].to_rust_type_hint(), "*const c_char");
+        assert_eq!(ts[2].to_rust_type_hint(), "[c_uchar; 4]");
+        assert_eq!(ts[3].to_rust_type_hint(), "S");
+        assert_eq!(ts[4].to_rust_type_hint(), "S");
+        assert_eq!(ts[5].to_rust_type_hint(), "Option<unsafe extern \"C\" fn(c_int, c_float) -> c_int>");
+        assert_eq!(ts[6].to_rust_type_hint(), "*mut c_void");
+
+        assert_eq!(ts[0].builtin_primitive(), Some(Primitive::Int));
+        assert_eq!(ts[1].builtin_primitive(), None);
+        assert_eq!(ts[1].get_pointee_type().unwrap().builtin_primitive(), Some(Primitive::Char));
+        let element = ts[2].get_element_type().unwrap();
+        assert_eq!(element.builtin_primitive(), Some(Primitive::UChar));
+        assert_eq!(ts[3].builtin_primitive(), None);
+        assert_eq!(ts[6].get_pointee_type().unwrap().builtin_primitive(), Some(Primitive::Void));
+    });
+
+    with_translation_unit(&clang, "test.c", "int (^b)(void);", &["-fblocks"], |_, _, tu| {
+        let type_ = tu.get_entity().get_children()[0].get_type().unwrap();
+        assert!(type_.is_block_pointer());
+        assert_eq!(type_.get_result_type().map(|t| t.get_kind()), Some(TypeKind::Int));
+        let pointee = type_.get_pointee_type().unwrap();
+        assert!(!pointee.is_block_pointer());
+        assert_eq!(pointee.get_result_type(), type_.get_result_type());
+    });
+
+    with_temporary_file("test.c", "int * _Nonnull a; int b;", |_, f| {
+        #[cfg(feature="clang_8_0")]
+        fn test_get_modified_type(types: &[Type]) {
+            assert_eq!(types[0].get_kind(), TypeKind::Attributed);
+            let modified = types[0].get_modified_type().unwrap();
+            assert_eq!(modified.get_kind(), TypeKind::Pointer);
+            assert_eq!(types[0].get_nullability(), Some(Nullability::NonNull));
+            assert_eq!(types[1].get_modified_type(), None);
+        }
+
+        #[cfg(not(feature="clang_8_0"))]
+        fn test_get_modified_type(_: &[Type]) { }
+
+        let index = Index::new(&clang, false, false);
+        #[cfg(feature="clang_8_0")]
+        let tu = index.parser(f).include_attributed_types(true).parse().unwrap();
+        #[cfg(not(feature="clang_8_0"))]
+        let tu = index.parser(f).parse().unwrap();
+        let types = tu.get_entity().get_children().iter().map(|e| {
+            e.get_type().unwrap()
+        }).collect::<Vec<_>>();
+        test_get_modified_type(&types);
+    });
+
+    let source = "
+        int * _Nullable a;
+        int * _Null_unspecified b;
+        int * c;
+        #if __has_feature(nullability_nullable_result)
+        int * _Nullable_result d;
+        #endif
+    ";
+
+    with_temporary_file("test.c", source, |_, f| {
+        #[cfg(feature="clang_8_0")]
+        fn test_get_nullability(clang: &Clang, f: &Path) {
+            let index = Index::new(clang, false, false);
+            let tu = index.parser(f).include_attributed_types(true).parse().unwrap();
+            let types = tu.get_entity().get_children().iter().map(|e| {
+                e.get_type().unwrap()
+            }).collect::<Vec<_>>();
+            assert_eq!(types[0].get_nullability(), Some(Nullability::Nullable));
+            assert_eq!(types[1].get_nullability(), Some(Nullability::Unspecified));
+            assert_eq!(types[2].get_nullability(), None);
+
+            #[cfg(feature="clang_12_0")]
+            assert_eq!(types[3].get_nullability(), Some(Nullability::NullableResult));
+        }
+
+        #[cfg(not(feature="clang_8_0"))]
+        fn test_get_nullability(_: &Clang, _: &Path) { }
+
+        test_get_nullability(&clang, f);
+    });
+
     let source = "
         int integer = 322;
         int* pointer = &integer;
@@ -1200,6 +2478,22 @@ fn test() {
     with_types(&clang, source, |ts| {
         assert_eq!(ts[0].get_template_argument_types(), None);
         assert_eq!(ts[1].get_template_argument_types(), Some(vec![Some(ts[0]), None]));
+        assert_eq!(ts[1].get_written_template_argument_types(), Some(vec![Some(ts[0]), None]));
+    });
+
+    let source = "
+        template <typename T, int I> class Class { int member; };
+        template <typename T> using Alias = Class<T, 322>;
+        typedef Alias<int> Typedef;
+        Typedef typedef_;
+    ";
+
+    with_entity(&clang, source, |e| {
+        let type_ = e.get_children()[3].get_type().unwrap();
+        let arguments = type_.get_template_argument_types().unwrap();
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[0].map(|t| t.get_kind()), Some(TypeKind::Int));
+        assert_eq!(arguments[1], None);
     });
 
     let source = "
@@ -1329,4 +2623,23 @@ fn test() {
     assert_eq!(Usr::from_objc_method(&class, "B", false), Usr("c:objc(cs)A(cm)B".into()));
     assert_eq!(Usr::from_objc_property(&class, "B"), Usr("c:objc(cs)A(py)B".into()));
     assert_eq!(Usr::from_objc_protocol("A"), Usr("c:objc(pl)A".into()));
+
+    assert_eq!(Usr("c:test.cpp@30@F@f#@a".into()).normalize(), Usr("c:test.cpp@F@f#@a".into()));
+    assert_eq!(Usr("c:test.cpp@8@macro@A".into()).normalize(), Usr("c:test.cpp@macro@A".into()));
+    assert_eq!(Usr("c:test.cpp@F@f".into()).normalize(), Usr("c:test.cpp@F@f".into()));
+    assert_eq!(Usr("c:@N@A@S@B".into()).normalize(), Usr("c:@N@A@S@B".into()));
+
+    with_entity(&clang, "void f() { int a; }", |e| {
+        let mut locals = vec![];
+        e.visit_children(|c, _| {
+            if c.get_kind() == EntityKind::VarDecl {
+                locals.push(c);
+            }
+            EntityVisitResult::Recurse
+        });
+
+        let usr = locals[0].get_usr().unwrap().normalize();
+        assert!(usr.0.ends_with("@F@f#@a"));
+        assert!(!usr.0.contains("@11@"));
+    });
 }
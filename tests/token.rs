@@ -24,6 +24,12 @@ pub fn test(clang: &Clang) {
         assert_token_eq!(tokens[3], Literal, "322", 1, 9, range!(file, 1, 9, 1, 12));
         assert_token_eq!(tokens[4], Punctuation, ";", 1, 12, range!(file, 1, 12, 1, 13));
 
+        let mut buffer = String::from("stale");
+        tokens[0].get_spelling_into(&mut buffer);
+        assert_eq!(buffer, "int");
+        tokens[3].get_spelling_into(&mut buffer);
+        assert_eq!(buffer, "322");
+
         fn test_annotate<'tu>(tu: &'tu TranslationUnit<'tu>, tokens: &[Token<'tu>]) {
             let declaration = tu.get_entity().get_children()[0];
             let literal = declaration.get_children()[0];
@@ -38,4 +44,13 @@ pub fn test(clang: &Clang) {
 
         test_annotate(&tu, &tokens);
     });
+
+    let source = "int f(int a,  int b) {\n    return a+b;\n}";
+    super::with_translation_unit(&clang, "test.cpp", source, &[], |_, _, tu| {
+        let tokens = tu.get_entity().get_children()[0].get_range().unwrap().tokenize();
+        assert_eq!(tu.reconstruct_text(&tokens), source);
+        assert_eq!(tu.reconstruct_text(&tokens[1..4]), "f(int");
+        assert_eq!(tu.reconstruct_text(&[tokens[1], tokens[0]]), "f int");
+        assert_eq!(tu.reconstruct_text(&[]), "");
+    });
 }